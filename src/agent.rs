@@ -2,9 +2,11 @@ use async_trait::async_trait;
 use std::collections::HashMap;
 
 use crate::errors::Result;
-use crate::request::{get, put};
+use crate::request::{get, get_with_status, post, put};
 use crate::{Client, QueryMeta, QueryOptions};
+use reqwest::StatusCode;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
 #[derive(Clone, Default, Serialize, Deserialize, Debug)]
 #[serde(default)]
@@ -26,20 +28,27 @@ pub struct TaggedAddress {
     pub Port: u16,
 }
 
-#[derive(Clone, Serialize, Deserialize, Debug, Default)]
+#[derive(Clone, Copy, Eq, PartialEq, Serialize, Deserialize, Debug, Default)]
 #[serde(default)]
-pub struct TaggedAddresses {
-    pub lan_ipv4: TaggedAddress,
-    pub wan_ipv4: TaggedAddress,
+pub struct AgentWeights {
+    pub Passing: i32,
+    pub Warning: i32,
 }
 
 #[derive(Clone, Serialize, Deserialize, Debug, Default)]
+#[serde(default)]
 pub struct RegisterAgentCheck {
+    #[serde(skip_serializing_if = "String::is_empty")]
     pub Name: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
     pub ID: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
     pub Interval: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
     pub Notes: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
     pub DeregisterCriticalServiceAfter: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
     pub Timeout: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub AliasNode: Option<String>,
@@ -66,6 +75,7 @@ pub struct RegisterAgentCheck {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub H2PingUseTLS: Option<bool>,
     // Http query check
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub HTTP: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub Method: Option<String>,
@@ -104,6 +114,84 @@ pub struct RegisterAgentCheck {
     pub FailuresBeforeCritical: Option<i32>,
 }
 
+impl RegisterAgentCheck {
+    /// A check that must be updated through `Agent::ttl_check_send` at least every `ttl`.
+    pub fn ttl(ttl: &str) -> Self {
+        RegisterAgentCheck {
+            TTL: Some(ttl.to_owned()),
+            ..Default::default()
+        }
+    }
+
+    /// A check that issues an HTTP GET to `url` every `interval`.
+    pub fn http(url: &str, interval: &str) -> Self {
+        RegisterAgentCheck {
+            HTTP: Some(url.to_owned()),
+            Interval: interval.to_owned(),
+            ..Default::default()
+        }
+    }
+
+    /// A check that opens a TCP connection to `address` every `interval`.
+    pub fn tcp(address: &str, interval: &str) -> Self {
+        RegisterAgentCheck {
+            TCP: Some(address.to_owned()),
+            Interval: interval.to_owned(),
+            ..Default::default()
+        }
+    }
+
+    /// A check that calls the gRPC health protocol on `target` every `interval`.
+    pub fn grpc(target: &str, interval: &str) -> Self {
+        RegisterAgentCheck {
+            GRPC: Some(target.to_owned()),
+            Interval: interval.to_owned(),
+            ..Default::default()
+        }
+    }
+
+    /// A check that mirrors the health of another service, on this node or on `node`.
+    pub fn alias(service: &str, node: Option<&str>) -> Self {
+        RegisterAgentCheck {
+            AliasService: Some(service.to_owned()),
+            AliasNode: node.map(String::from),
+            ..Default::default()
+        }
+    }
+
+    pub fn name(mut self, name: &str) -> Self {
+        self.Name = name.to_owned();
+        self
+    }
+
+    pub fn id(mut self, id: &str) -> Self {
+        self.ID = id.to_owned();
+        self
+    }
+
+    pub fn notes(mut self, notes: &str) -> Self {
+        self.Notes = notes.to_owned();
+        self
+    }
+
+    pub fn timeout(mut self, timeout: &str) -> Self {
+        self.Timeout = timeout.to_owned();
+        self
+    }
+
+    pub fn deregister_critical_service_after(mut self, after: &str) -> Self {
+        self.DeregisterCriticalServiceAfter = after.to_owned();
+        self
+    }
+
+    /// Initial status of the check, one of `passing`, `warning` or `critical`.
+    pub fn status(mut self, status: &str) -> Self {
+        self.Status = Some(status.to_owned());
+        self
+    }
+}
+
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
 pub enum TTLStatus {
     PASS,
     WARN,
@@ -120,14 +208,53 @@ impl TTLStatus {
     }
 }
 
-#[derive(Clone, Default, Serialize, Deserialize, Debug)]
+/// Serf status of a cluster member, as reported by `/v1/agent/members`.
+#[derive(Clone, Copy, Default, Eq, PartialEq, Hash, Serialize, Deserialize, Debug)]
+#[serde(from = "u8", into = "u8")]
+pub enum MemberStatus {
+    #[default]
+    None,
+    Alive,
+    Leaving,
+    Left,
+    Failed,
+    Unknown(u8),
+}
+
+impl From<u8> for MemberStatus {
+    fn from(status: u8) -> Self {
+        match status {
+            0 => MemberStatus::None,
+            1 => MemberStatus::Alive,
+            2 => MemberStatus::Leaving,
+            3 => MemberStatus::Left,
+            4 => MemberStatus::Failed,
+            other => MemberStatus::Unknown(other),
+        }
+    }
+}
+
+impl From<MemberStatus> for u8 {
+    fn from(status: MemberStatus) -> Self {
+        match status {
+            MemberStatus::None => 0,
+            MemberStatus::Alive => 1,
+            MemberStatus::Leaving => 2,
+            MemberStatus::Left => 3,
+            MemberStatus::Failed => 4,
+            MemberStatus::Unknown(other) => other,
+        }
+    }
+}
+
+#[derive(Clone, Default, Eq, PartialEq, Serialize, Deserialize, Debug)]
 #[serde(default)]
 pub struct AgentMember {
     pub Name: String,
     pub Addr: String,
     pub Port: u16,
     pub Tags: HashMap<String, String>,
-    pub pubStatus: usize,
+    pub Status: MemberStatus,
     pub ProtocolMin: u8,
     pub ProtocolMax: u8,
     pub ProtocolCur: u8,
@@ -136,25 +263,253 @@ pub struct AgentMember {
     pub DelegateCur: u8,
 }
 
+/// The node the agent runs on, from `Agent::agent_self`.
+#[derive(Clone, Default, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct AgentSelfConfig {
+    pub Datacenter: String,
+    pub NodeName: String,
+    pub NodeID: String,
+    pub Server: bool,
+    pub Revision: String,
+    pub Version: String,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct AgentSelf {
+    pub Config: AgentSelfConfig,
+    pub Member: AgentMember,
+    pub Meta: HashMap<String, String>,
+}
+
+/// Difference between two snapshots of `Agent::members`, keyed by member name.
+#[derive(Clone, Default, Eq, PartialEq, Debug)]
+pub struct MembershipDiff {
+    /// Members present only in the new snapshot.
+    pub added: Vec<AgentMember>,
+    /// Members present only in the old snapshot.
+    pub removed: Vec<AgentMember>,
+    /// Members whose status changed, as `(old, new)` pairs.
+    pub status_changed: Vec<(AgentMember, AgentMember)>,
+}
+
+impl MembershipDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.status_changed.is_empty()
+    }
+}
+
+/// Compares two membership snapshots. Results keep the order of the snapshot they come from.
+pub fn diff_members(old: &[AgentMember], new: &[AgentMember]) -> MembershipDiff {
+    let old_by_name: HashMap<&str, &AgentMember> =
+        old.iter().map(|m| (m.Name.as_str(), m)).collect();
+    let new_by_name: HashMap<&str, &AgentMember> =
+        new.iter().map(|m| (m.Name.as_str(), m)).collect();
+    let mut diff = MembershipDiff::default();
+    for member in new {
+        match old_by_name.get(member.Name.as_str()) {
+            None => diff.added.push(member.clone()),
+            Some(prev) if prev.Status != member.Status => {
+                diff.status_changed.push(((*prev).clone(), member.clone()))
+            }
+            Some(_) => {}
+        }
+    }
+    for member in old {
+        if !new_by_name.contains_key(member.Name.as_str()) {
+            diff.removed.push(member.clone());
+        }
+    }
+    diff
+}
+
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct MeshGatewayConfig {
+    /// One of `none`, `local` or `remote`, empty for the default.
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Mode: String,
+}
+
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct ExposePath {
+    pub ListenerPort: u16,
+    pub Path: String,
+    pub LocalPathPort: u16,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Protocol: String,
+}
+
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct ExposeConfig {
+    pub Checks: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub Paths: Vec<ExposePath>,
+}
+
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct TransparentProxyConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub OutboundListenerPort: Option<u16>,
+    pub DialedDirectly: bool,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct Upstream {
+    /// `service` (the default) or `prepared_query`.
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub DestinationType: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub DestinationNamespace: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub DestinationPartition: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub DestinationPeer: Option<String>,
+    pub DestinationName: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Datacenter: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub LocalBindAddress: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub LocalBindPort: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub LocalBindSocketPath: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Config: Option<HashMap<String, Value>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub MeshGateway: Option<MeshGatewayConfig>,
+}
+
+impl Upstream {
+    /// An upstream to `service`, reachable on `local_bind_port` of the proxy.
+    pub fn new(service: &str, local_bind_port: u16) -> Self {
+        Upstream {
+            DestinationName: service.to_owned(),
+            LocalBindPort: Some(local_bind_port),
+            ..Default::default()
+        }
+    }
+
+    pub fn datacenter(mut self, datacenter: &str) -> Self {
+        self.Datacenter = Some(datacenter.to_owned());
+        self
+    }
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct AgentServiceConnectProxyConfig {
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub DestinationServiceName: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub DestinationServiceID: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub LocalServiceAddress: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub LocalServicePort: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub LocalServiceSocketPath: Option<String>,
+    /// `transparent` or `direct`, empty for the default.
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Mode: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Config: Option<HashMap<String, Value>>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub Upstreams: Vec<Upstream>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub MeshGateway: Option<MeshGatewayConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Expose: Option<ExposeConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub TransparentProxy: Option<TransparentProxyConfig>,
+}
+
+impl AgentServiceConnectProxyConfig {
+    pub fn upstream(mut self, upstream: Upstream) -> Self {
+        self.Upstreams.push(upstream);
+        self
+    }
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct AgentServiceConnect {
+    pub Native: bool,
+    /// Only used at registration time, the agent registers it as a separate service.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub SidecarService: Option<Box<RegisterAgentService>>,
+}
+
 #[derive(Clone, Default, Serialize, Deserialize, Debug)]
 #[serde(default)]
 pub struct AgentService {
+    /// Empty for a typical service, otherwise `connect-proxy`, `mesh-gateway`,
+    /// `terminating-gateway`, `ingress-gateway` or `api-gateway`.
+    pub Kind: String,
     pub ID: String,
     pub Service: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub Tags: Option<Vec<String>>,
     pub Port: u16,
     pub Address: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub SocketPath: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub TaggedAddresses: Option<HashMap<String, TaggedAddress>>,
     pub EnableTagOverride: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub CreateIndex: Option<u64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub ModifyIndex: Option<u64>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub Weights: Option<HashMap<String, i32>>,
+    pub Weights: Option<AgentWeights>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Meta: Option<HashMap<String, String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ContentHash: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Proxy: Option<AgentServiceConnectProxyConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Connect: Option<AgentServiceConnect>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Namespace: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Partition: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Datacenter: Option<String>,
+}
+
+#[derive(Serialize, Debug)]
+struct AgentAuthorizeParams<'a> {
+    Target: &'a str,
+    ClientCertURI: &'a str,
+    ClientCertSerial: &'a str,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct AgentAuthorize {
+    pub Authorized: bool,
+    pub Reason: String,
+}
+
+/// A local service together with its checks, as returned by the agent-local health endpoints.
+#[derive(Clone, Default, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct AgentServiceChecksInfo {
+    /// One of `passing`, `warning`, `critical` or `maintenance`.
+    pub AggregatedStatus: String,
+    pub Service: AgentService,
+    pub Checks: Vec<AgentCheck>,
 }
 
 #[derive(Clone, Serialize, Deserialize, Debug, Default)]
+#[serde(default)]
 pub struct RegisterAgentService {
     pub Name: String,
     pub ID: String,
@@ -165,24 +520,148 @@ pub struct RegisterAgentService {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub Tags: Option<Vec<String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub TaggedAddresses: Option<TaggedAddresses>,
+    pub TaggedAddresses: Option<HashMap<String, TaggedAddress>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub Meta: Option<HashMap<String, String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub Check: Option<AgentCheck>,
+    pub Check: Option<RegisterAgentCheck>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub Checks: Vec<RegisterAgentCheck>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Weights: Option<AgentWeights>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub SocketPath: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
-    pub Weights: Option<HashMap<String, i32>>,
+    pub Proxy: Option<AgentServiceConnectProxyConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Connect: Option<AgentServiceConnect>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Namespace: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Partition: Option<String>,
+}
+
+impl RegisterAgentService {
+    pub fn new(name: &str) -> Self {
+        RegisterAgentService {
+            Name: name.to_owned(),
+            ..Default::default()
+        }
+    }
+
+    pub fn id(mut self, id: &str) -> Self {
+        self.ID = id.to_owned();
+        self
+    }
+
+    pub fn address(mut self, address: &str) -> Self {
+        self.Address = address.to_owned();
+        self
+    }
+
+    pub fn port(mut self, port: u16) -> Self {
+        self.Port = port;
+        self
+    }
+
+    /// Registers the service on a unix socket instead of an address and port.
+    pub fn socket_path(mut self, path: &str) -> Self {
+        self.SocketPath = Some(path.to_owned());
+        self
+    }
+
+    pub fn kind(mut self, kind: &str) -> Self {
+        self.Kind = kind.to_owned();
+        self
+    }
+
+    pub fn tag(mut self, tag: &str) -> Self {
+        self.Tags.get_or_insert_with(Vec::new).push(tag.to_owned());
+        self
+    }
+
+    pub fn tagged_address(mut self, name: &str, address: &str, port: u16) -> Self {
+        self.TaggedAddresses
+            .get_or_insert_with(HashMap::new)
+            .insert(
+                name.to_owned(),
+                TaggedAddress {
+                    Address: address.to_owned(),
+                    Port: port,
+                },
+            );
+        self
+    }
+
+    pub fn meta(mut self, key: &str, value: &str) -> Self {
+        self.Meta
+            .get_or_insert_with(HashMap::new)
+            .insert(key.to_owned(), value.to_owned());
+        self
+    }
+
+    /// Adds a check to `Checks`, can be called several times.
+    pub fn check(mut self, check: RegisterAgentCheck) -> Self {
+        self.Checks.push(check);
+        self
+    }
+
+    pub fn weights(mut self, passing: i32, warning: i32) -> Self {
+        self.Weights = Some(AgentWeights {
+            Passing: passing,
+            Warning: warning,
+        });
+        self
+    }
+
+    pub fn enable_tag_override(mut self, enable: bool) -> Self {
+        self.EnableTagOverride = enable;
+        self
+    }
+
+    /// Makes this service a `connect-proxy` for `proxy.DestinationServiceName`.
+    pub fn proxy(mut self, proxy: AgentServiceConnectProxyConfig) -> Self {
+        self.Kind = String::from("connect-proxy");
+        self.Proxy = Some(proxy);
+        self
+    }
+
+    /// Marks the service as natively integrated with Connect.
+    pub fn connect_native(mut self) -> Self {
+        self.Connect.get_or_insert_with(Default::default).Native = true;
+        self
+    }
+
+    /// Registers a sidecar proxy along with the service. Empty fields of `sidecar`
+    /// are filled in by the agent from the parent service.
+    pub fn sidecar(mut self, sidecar: RegisterAgentService) -> Self {
+        self.Connect
+            .get_or_insert_with(Default::default)
+            .SidecarService = Some(Box::new(sidecar));
+        self
+    }
+
+    pub fn namespace(mut self, namespace: &str) -> Self {
+        self.Namespace = Some(namespace.to_owned());
+        self
+    }
+
+    pub fn partition(mut self, partition: &str) -> Self {
+        self.Partition = Some(partition.to_owned());
+        self
+    }
 }
 
 #[async_trait]
 pub trait Agent {
-    async fn members(&self, wan: bool) -> Result<AgentMember>;
+    async fn agent_self(&self) -> Result<AgentSelf>;
+    async fn members(&self, wan: bool, segment: Option<&str>) -> Result<Vec<AgentMember>>;
     async fn reload(&self) -> Result<()>;
     async fn maintenance_mode(&self, enable: bool, reason: Option<&str>) -> Result<()>;
     async fn join(&self, address: &str, wan: bool) -> Result<()>;
     async fn leave(&self) -> Result<()>;
-    async fn force_leave(&self) -> Result<()>;
-    async fn checks(&self) -> Result<HashMap<String, AgentCheck>>;
+    async fn force_leave(&self, node: &str, prune: bool, wan: bool) -> Result<()>;
+    async fn checks(&self, filter: Option<&str>) -> Result<HashMap<String, AgentCheck>>;
     async fn register_check(&self, reg: &RegisterAgentCheck) -> Result<()>;
     async fn deregister_check(&self, check_id: &str) -> Result<()>;
     async fn ttl_check_send(
@@ -192,6 +671,21 @@ pub trait Agent {
         note: Option<&str>,
     ) -> Result<()>;
     async fn agent_services(&self, filter: Option<&str>) -> Result<HashMap<String, AgentService>>;
+    async fn agent_service(
+        &self,
+        service_id: &str,
+        q: Option<&QueryOptions>,
+    ) -> Result<(AgentService, QueryMeta)>;
+    async fn health_service_by_id(
+        &self,
+        service_id: &str,
+        q: Option<&QueryOptions>,
+    ) -> Result<(AgentServiceChecksInfo, QueryMeta)>;
+    async fn health_service_by_name(
+        &self,
+        service: &str,
+        q: Option<&QueryOptions>,
+    ) -> Result<(Vec<AgentServiceChecksInfo>, QueryMeta)>;
     async fn register_service(
         &self,
         reg: &RegisterAgentService,
@@ -204,16 +698,32 @@ pub trait Agent {
         enable: bool,
         reason: Option<&str>,
     ) -> Result<()>;
+    async fn connect_authorize(
+        &self,
+        target: &str,
+        client_cert_uri: &str,
+        client_cert_serial: &str,
+    ) -> Result<AgentAuthorize>;
 }
 
 #[async_trait]
 impl Agent for Client {
+    /// https://developer.hashicorp.com/consul/api-docs/agent#read-configuration
+    async fn agent_self(&self) -> Result<AgentSelf> {
+        get("/v1/agent/self", &self.config, HashMap::new(), None)
+            .await
+            .map(|x| x.0)
+    }
+
     /// https://www.consul.io/api/agent.html#list-members
-    async fn members(&self, wan: bool) -> Result<AgentMember> {
+    async fn members(&self, wan: bool, segment: Option<&str>) -> Result<Vec<AgentMember>> {
         let mut params = HashMap::new();
         if wan {
             params.insert(String::from("wan"), String::from("1"));
         }
+        if let Some(segment) = segment {
+            params.insert(String::from("segment"), segment.to_owned());
+        }
         get("/v1/agent/members", &self.config, params, None)
             .await
             .map(|x| x.0)
@@ -282,21 +792,27 @@ impl Agent for Client {
     }
 
     ///https://www.consul.io/api/agent.html#force-leave-and-shutdown
-    async fn force_leave(&self) -> Result<()> {
-        put(
-            "/v1/agent/force-leave",
-            None as Option<&()>,
-            &self.config,
-            HashMap::new(),
-            None,
-        )
-        .await
-        .map(|x| x.0)
+    async fn force_leave(&self, node: &str, prune: bool, wan: bool) -> Result<()> {
+        let mut params = HashMap::new();
+        if prune {
+            params.insert(String::from("prune"), String::from("true"));
+        }
+        if wan {
+            params.insert(String::from("wan"), String::from("true"));
+        }
+        let path = format!("/v1/agent/force-leave/{}", node);
+        put(&path, None as Option<&()>, &self.config, params, None)
+            .await
+            .map(|x| x.0)
     }
 
     /// https://www.consul.io/api/agent/check.html#list-checks
-    async fn checks(&self) -> Result<HashMap<String, AgentCheck>> {
-        get("/v1/agent/checks", &self.config, HashMap::new(), None)
+    async fn checks(&self, filter: Option<&str>) -> Result<HashMap<String, AgentCheck>> {
+        let mut params = HashMap::new();
+        if let Some(filter) = filter {
+            params.insert("filter".to_string(), filter.to_string());
+        }
+        get("/v1/agent/checks", &self.config, params, None)
             .await
             .map(|x| x.0)
     }
@@ -360,6 +876,62 @@ impl Agent for Client {
             .map(|x| x.0)
     }
 
+    /// https://developer.hashicorp.com/consul/api-docs/agent/service#get-service-configuration
+    async fn agent_service(
+        &self,
+        service_id: &str,
+        q: Option<&QueryOptions>,
+    ) -> Result<(AgentService, QueryMeta)> {
+        let path = format!("/v1/agent/service/{}", service_id);
+        get(&path, &self.config, HashMap::new(), q).await
+    }
+
+    /// https://developer.hashicorp.com/consul/api-docs/agent/service#get-local-service-health-by-id
+    ///
+    /// Warning and critical services are returned as values, check `AggregatedStatus`.
+    async fn health_service_by_id(
+        &self,
+        service_id: &str,
+        q: Option<&QueryOptions>,
+    ) -> Result<(AgentServiceChecksInfo, QueryMeta)> {
+        let path = format!("/v1/agent/health/service/id/{}", service_id);
+        get_with_status(
+            &path,
+            &self.config,
+            HashMap::new(),
+            q,
+            &[
+                StatusCode::TOO_MANY_REQUESTS,
+                StatusCode::SERVICE_UNAVAILABLE,
+            ],
+        )
+        .await
+    }
+
+    /// https://developer.hashicorp.com/consul/api-docs/agent/service#get-local-service-health
+    ///
+    /// Warning and critical services are returned as values, check `AggregatedStatus`.
+    async fn health_service_by_name(
+        &self,
+        service: &str,
+        q: Option<&QueryOptions>,
+    ) -> Result<(Vec<AgentServiceChecksInfo>, QueryMeta)> {
+        let path = format!("/v1/agent/health/service/name/{}", service);
+        get_with_status(
+            &path,
+            &self.config,
+            HashMap::new(),
+            q,
+            // The agent answers 404 with an empty list when no local instance matches
+            &[
+                StatusCode::NOT_FOUND,
+                StatusCode::TOO_MANY_REQUESTS,
+                StatusCode::SERVICE_UNAVAILABLE,
+            ],
+        )
+        .await
+    }
+
     /// https://developer.hashicorp.com/consul/api-docs/agent/service#register-service
     async fn register_service(
         &self,
@@ -420,4 +992,29 @@ impl Agent for Client {
         .await
         .map(|x| x.0)
     }
+
+    /// https://developer.hashicorp.com/consul/api-docs/agent/connect#authorize
+    ///
+    /// Checks the intentions for a connection from the client certificate to `target`.
+    async fn connect_authorize(
+        &self,
+        target: &str,
+        client_cert_uri: &str,
+        client_cert_serial: &str,
+    ) -> Result<AgentAuthorize> {
+        let params = AgentAuthorizeParams {
+            Target: target,
+            ClientCertURI: client_cert_uri,
+            ClientCertSerial: client_cert_serial,
+        };
+        post(
+            "/v1/agent/connect/authorize",
+            Some(&params),
+            &self.config,
+            HashMap::new(),
+            None,
+        )
+        .await
+        .map(|x| x.0)
+    }
 }
@@ -0,0 +1,960 @@
+use async_trait::async_trait;
+use std::collections::HashMap;
+
+use crate::agent::{ExposeConfig, MeshGatewayConfig, TransparentProxyConfig};
+use crate::errors::Result;
+use crate::request::{delete, get, put};
+use crate::{Client, QueryMeta, QueryOptions, WriteMeta, WriteOptions};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+pub const SERVICE_DEFAULTS: &str = "service-defaults";
+pub const PROXY_DEFAULTS: &str = "proxy-defaults";
+pub const SERVICE_ROUTER: &str = "service-router";
+pub const SERVICE_SPLITTER: &str = "service-splitter";
+pub const SERVICE_RESOLVER: &str = "service-resolver";
+pub const INGRESS_GATEWAY: &str = "ingress-gateway";
+pub const TERMINATING_GATEWAY: &str = "terminating-gateway";
+pub const MESH: &str = "mesh";
+pub const EXPORTED_SERVICES: &str = "exported-services";
+pub const SERVICE_INTENTIONS: &str = "service-intentions";
+
+/// A configuration entry, dispatched on its `Kind`.
+///
+/// Fields that aren't modeled are kept in the `Extra` map of each entry, so that an
+/// entry read from Consul can be written back without losing them.
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(tag = "Kind")]
+pub enum ConfigEntry {
+    #[serde(rename = "service-defaults")]
+    ServiceDefaults(ServiceConfigEntry),
+    #[serde(rename = "proxy-defaults")]
+    ProxyDefaults(ProxyConfigEntry),
+    #[serde(rename = "service-router")]
+    ServiceRouter(ServiceRouterConfigEntry),
+    #[serde(rename = "service-splitter")]
+    ServiceSplitter(ServiceSplitterConfigEntry),
+    #[serde(rename = "service-resolver")]
+    ServiceResolver(ServiceResolverConfigEntry),
+    #[serde(rename = "ingress-gateway")]
+    IngressGateway(IngressGatewayConfigEntry),
+    #[serde(rename = "terminating-gateway")]
+    TerminatingGateway(TerminatingGatewayConfigEntry),
+    #[serde(rename = "mesh")]
+    Mesh(MeshConfigEntry),
+    #[serde(rename = "exported-services")]
+    ExportedServices(ExportedServicesConfigEntry),
+    #[serde(rename = "service-intentions")]
+    ServiceIntentions(ServiceIntentionsConfigEntry),
+}
+
+impl ConfigEntry {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            ConfigEntry::ServiceDefaults(_) => SERVICE_DEFAULTS,
+            ConfigEntry::ProxyDefaults(_) => PROXY_DEFAULTS,
+            ConfigEntry::ServiceRouter(_) => SERVICE_ROUTER,
+            ConfigEntry::ServiceSplitter(_) => SERVICE_SPLITTER,
+            ConfigEntry::ServiceResolver(_) => SERVICE_RESOLVER,
+            ConfigEntry::IngressGateway(_) => INGRESS_GATEWAY,
+            ConfigEntry::TerminatingGateway(_) => TERMINATING_GATEWAY,
+            ConfigEntry::Mesh(_) => MESH,
+            ConfigEntry::ExportedServices(_) => EXPORTED_SERVICES,
+            ConfigEntry::ServiceIntentions(_) => SERVICE_INTENTIONS,
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        match self {
+            ConfigEntry::ServiceDefaults(e) => &e.Name,
+            ConfigEntry::ProxyDefaults(e) => &e.Name,
+            ConfigEntry::ServiceRouter(e) => &e.Name,
+            ConfigEntry::ServiceSplitter(e) => &e.Name,
+            ConfigEntry::ServiceResolver(e) => &e.Name,
+            ConfigEntry::IngressGateway(e) => &e.Name,
+            ConfigEntry::TerminatingGateway(e) => &e.Name,
+            ConfigEntry::Mesh(_) => MESH,
+            ConfigEntry::ExportedServices(e) => &e.Name,
+            ConfigEntry::ServiceIntentions(e) => &e.Name,
+        }
+    }
+
+    pub fn modify_index(&self) -> u64 {
+        match self {
+            ConfigEntry::ServiceDefaults(e) => e.ModifyIndex,
+            ConfigEntry::ProxyDefaults(e) => e.ModifyIndex,
+            ConfigEntry::ServiceRouter(e) => e.ModifyIndex,
+            ConfigEntry::ServiceSplitter(e) => e.ModifyIndex,
+            ConfigEntry::ServiceResolver(e) => e.ModifyIndex,
+            ConfigEntry::IngressGateway(e) => e.ModifyIndex,
+            ConfigEntry::TerminatingGateway(e) => e.ModifyIndex,
+            ConfigEntry::Mesh(e) => e.ModifyIndex,
+            ConfigEntry::ExportedServices(e) => e.ModifyIndex,
+            ConfigEntry::ServiceIntentions(e) => e.ModifyIndex,
+        }
+    }
+}
+
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct HTTPHeaderModifiers {
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub Add: HashMap<String, String>,
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub Set: HashMap<String, String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub Remove: Vec<String>,
+}
+
+/// Matches an HTTP header. Only one of `Present`, `Exact`, `Prefix`, `Suffix`
+/// and `Regex` may be set.
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct HTTPHeaderMatch {
+    pub Name: String,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub Present: bool,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Exact: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Prefix: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Suffix: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Regex: String,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub Invert: bool,
+}
+
+// service-defaults
+
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct UpstreamLimits {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub MaxConnections: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub MaxPendingRequests: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub MaxConcurrentRequests: Option<u32>,
+}
+
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct PassiveHealthCheck {
+    /// Duration, e.g. `10s`
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Interval: String,
+    pub MaxFailures: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub EnforcingConsecutive5xx: Option<u32>,
+}
+
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct UpstreamConfig {
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Name: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Partition: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Namespace: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Peer: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Protocol: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ConnectTimeoutMs: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Limits: Option<UpstreamLimits>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub PassiveHealthCheck: Option<PassiveHealthCheck>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub MeshGateway: Option<MeshGatewayConfig>,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub BalanceOutboundConnections: String,
+}
+
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct UpstreamConfiguration {
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub Overrides: Vec<UpstreamConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Defaults: Option<UpstreamConfig>,
+}
+
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct DestinationConfig {
+    pub Addresses: Vec<String>,
+    pub Port: u16,
+}
+
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct ServiceConfigEntry {
+    pub Name: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Partition: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Namespace: String,
+    /// `tcp` (the default), `http`, `http2` or `grpc`
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Protocol: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Mode: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub TransparentProxy: Option<TransparentProxyConfig>,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub MutualTLSMode: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub MeshGateway: Option<MeshGatewayConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Expose: Option<ExposeConfig>,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub ExternalSNI: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub UpstreamConfig: Option<UpstreamConfiguration>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Destination: Option<DestinationConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub MaxInboundConnections: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub LocalConnectTimeoutMs: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub LocalRequestTimeoutMs: Option<u32>,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub BalanceInboundConnections: String,
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub Meta: HashMap<String, String>,
+    pub CreateIndex: u64,
+    pub ModifyIndex: u64,
+    #[serde(flatten)]
+    pub Extra: HashMap<String, Value>,
+}
+
+// proxy-defaults
+
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct ProxyConfigEntry {
+    /// Always `global`
+    pub Name: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Partition: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Namespace: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Mode: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub TransparentProxy: Option<TransparentProxyConfig>,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub MutualTLSMode: String,
+    /// Opaque proxy configuration, e.g. `protocol` or Envoy escape hatches
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub Config: HashMap<String, Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub MeshGateway: Option<MeshGatewayConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Expose: Option<ExposeConfig>,
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub Meta: HashMap<String, String>,
+    pub CreateIndex: u64,
+    pub ModifyIndex: u64,
+    #[serde(flatten)]
+    pub Extra: HashMap<String, Value>,
+}
+
+// service-router
+
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct ServiceRouteHTTPMatchQueryParam {
+    pub Name: String,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub Present: bool,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Exact: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Regex: String,
+}
+
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct ServiceRouteHTTPMatch {
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub PathExact: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub PathPrefix: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub PathRegex: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub Header: Vec<HTTPHeaderMatch>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub QueryParam: Vec<ServiceRouteHTTPMatchQueryParam>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub Methods: Vec<String>,
+}
+
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct ServiceRouteMatch {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub HTTP: Option<ServiceRouteHTTPMatch>,
+}
+
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct ServiceRouteDestination {
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Service: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub ServiceSubset: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Namespace: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Partition: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub PrefixRewrite: String,
+    /// Duration, e.g. `15s`
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub RequestTimeout: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub IdleTimeout: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub NumRetries: Option<u32>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub RetryOnConnectFailure: bool,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub RetryOnStatusCodes: Vec<u32>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub RetryOn: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub RequestHeaders: Option<HTTPHeaderModifiers>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ResponseHeaders: Option<HTTPHeaderModifiers>,
+}
+
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct ServiceRoute {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Match: Option<ServiceRouteMatch>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Destination: Option<ServiceRouteDestination>,
+}
+
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct ServiceRouterConfigEntry {
+    pub Name: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Partition: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Namespace: String,
+    /// Evaluated in order, the first matching route wins
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub Routes: Vec<ServiceRoute>,
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub Meta: HashMap<String, String>,
+    pub CreateIndex: u64,
+    pub ModifyIndex: u64,
+    #[serde(flatten)]
+    pub Extra: HashMap<String, Value>,
+}
+
+// service-splitter
+
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct ServiceSplit {
+    /// Percentage of the traffic, the weights of a splitter add up to 100
+    pub Weight: f32,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Service: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub ServiceSubset: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Namespace: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Partition: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub RequestHeaders: Option<HTTPHeaderModifiers>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ResponseHeaders: Option<HTTPHeaderModifiers>,
+}
+
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct ServiceSplitterConfigEntry {
+    pub Name: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Partition: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Namespace: String,
+    pub Splits: Vec<ServiceSplit>,
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub Meta: HashMap<String, String>,
+    pub CreateIndex: u64,
+    pub ModifyIndex: u64,
+    #[serde(flatten)]
+    pub Extra: HashMap<String, Value>,
+}
+
+// service-resolver
+
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct ServiceResolverSubset {
+    /// Filter expression on the service instances
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Filter: String,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub OnlyPassing: bool,
+}
+
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct ServiceResolverRedirect {
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Service: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub ServiceSubset: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Namespace: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Partition: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Datacenter: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Peer: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub SamenessGroup: String,
+}
+
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct ServiceResolverFailoverTarget {
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Service: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub ServiceSubset: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Partition: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Namespace: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Datacenter: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Peer: String,
+}
+
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct ServiceResolverFailover {
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Service: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub ServiceSubset: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Namespace: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub Datacenters: Vec<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub Targets: Vec<ServiceResolverFailoverTarget>,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub SamenessGroup: String,
+}
+
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct RingHashConfig {
+    pub MinimumRingSize: u64,
+    pub MaximumRingSize: u64,
+}
+
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct LeastRequestConfig {
+    pub ChoiceCount: u32,
+}
+
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct CookieConfig {
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub Session: bool,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub TTL: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Path: String,
+}
+
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct HashPolicy {
+    /// `header`, `cookie` or `query_parameter`
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Field: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub FieldValue: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub CookieConfig: Option<CookieConfig>,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub SourceIP: bool,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub Terminal: bool,
+}
+
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct LoadBalancer {
+    /// `round_robin`, `least_request`, `ring_hash`, `maglev` or `random`
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Policy: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub RingHashConfig: Option<RingHashConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub LeastRequestConfig: Option<LeastRequestConfig>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub HashPolicies: Vec<HashPolicy>,
+}
+
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct ServiceResolverConfigEntry {
+    pub Name: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Partition: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Namespace: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub DefaultSubset: String,
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub Subsets: HashMap<String, ServiceResolverSubset>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Redirect: Option<ServiceResolverRedirect>,
+    /// Failover policies by subset name, `*` applies to all subsets
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub Failover: HashMap<String, ServiceResolverFailover>,
+    /// Duration, e.g. `5s`
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub ConnectTimeout: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub RequestTimeout: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub LoadBalancer: Option<LoadBalancer>,
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub Meta: HashMap<String, String>,
+    pub CreateIndex: u64,
+    pub ModifyIndex: u64,
+    #[serde(flatten)]
+    pub Extra: HashMap<String, Value>,
+}
+
+// ingress-gateway and terminating-gateway
+
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct GatewayTLSConfig {
+    pub Enabled: bool,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub TLSMinVersion: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub TLSMaxVersion: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub CipherSuites: Vec<String>,
+    /// Secret discovery service configuration
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub SDS: Option<Value>,
+}
+
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct IngressService {
+    pub Name: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub Hosts: Vec<String>,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Namespace: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Partition: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub TLS: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub RequestHeaders: Option<HTTPHeaderModifiers>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ResponseHeaders: Option<HTTPHeaderModifiers>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub MaxConnections: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub MaxPendingRequests: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub MaxConcurrentRequests: Option<u32>,
+}
+
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct IngressListener {
+    pub Port: u16,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Protocol: String,
+    pub Services: Vec<IngressService>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub TLS: Option<GatewayTLSConfig>,
+}
+
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct IngressGatewayConfigEntry {
+    pub Name: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Partition: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Namespace: String,
+    pub TLS: GatewayTLSConfig,
+    pub Listeners: Vec<IngressListener>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Defaults: Option<Value>,
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub Meta: HashMap<String, String>,
+    pub CreateIndex: u64,
+    pub ModifyIndex: u64,
+    #[serde(flatten)]
+    pub Extra: HashMap<String, Value>,
+}
+
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct LinkedService {
+    pub Name: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Namespace: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Partition: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub CAFile: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub CertFile: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub KeyFile: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub SNI: String,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub DisableAutoHostRewrite: bool,
+}
+
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct TerminatingGatewayConfigEntry {
+    pub Name: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Partition: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Namespace: String,
+    pub Services: Vec<LinkedService>,
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub Meta: HashMap<String, String>,
+    pub CreateIndex: u64,
+    pub ModifyIndex: u64,
+    #[serde(flatten)]
+    pub Extra: HashMap<String, Value>,
+}
+
+// mesh
+
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct TransparentProxyMeshConfig {
+    pub MeshDestinationsOnly: bool,
+}
+
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct MeshDirectionalTLSConfig {
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub TLSMinVersion: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub TLSMaxVersion: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub CipherSuites: Vec<String>,
+}
+
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct MeshTLSConfig {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Incoming: Option<MeshDirectionalTLSConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Outgoing: Option<MeshDirectionalTLSConfig>,
+}
+
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct MeshHTTPConfig {
+    pub SanitizeXForwardedClientCert: bool,
+}
+
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct PeeringMeshConfig {
+    pub PeerThroughMeshGateways: bool,
+}
+
+/// The `mesh` entry is a singleton, its name is always `mesh`.
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct MeshConfigEntry {
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Partition: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Namespace: String,
+    pub TransparentProxy: TransparentProxyMeshConfig,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub AllowEnablingPermissiveMutualTLS: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub TLS: Option<MeshTLSConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub HTTP: Option<MeshHTTPConfig>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Peering: Option<PeeringMeshConfig>,
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub Meta: HashMap<String, String>,
+    pub CreateIndex: u64,
+    pub ModifyIndex: u64,
+    #[serde(flatten)]
+    pub Extra: HashMap<String, Value>,
+}
+
+// exported-services
+
+/// A consumer of exported services. Only one of the fields may be set.
+#[derive(Clone, Default, Eq, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct ServiceConsumer {
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Partition: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Peer: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub SamenessGroup: String,
+}
+
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct ExportedService {
+    /// Service name, `*` exports all the services of the namespace
+    pub Name: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Namespace: String,
+    pub Consumers: Vec<ServiceConsumer>,
+}
+
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct ExportedServicesConfigEntry {
+    /// Name of the partition the services are exported from, `default` outside of
+    /// Consul Enterprise
+    pub Name: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Partition: String,
+    pub Services: Vec<ExportedService>,
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub Meta: HashMap<String, String>,
+    pub CreateIndex: u64,
+    pub ModifyIndex: u64,
+    #[serde(flatten)]
+    pub Extra: HashMap<String, Value>,
+}
+
+// service-intentions
+
+#[derive(Clone, Copy, Eq, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum IntentionAction {
+    Allow,
+    Deny,
+}
+
+/// L7 match of an intention permission. At most one of the path fields may be set.
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct IntentionHTTPPermission {
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub PathExact: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub PathPrefix: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub PathRegex: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub Header: Vec<HTTPHeaderMatch>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub Methods: Vec<String>,
+}
+
+#[derive(Clone, PartialEq, Serialize, Deserialize, Debug)]
+pub struct IntentionPermission {
+    pub Action: IntentionAction,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub HTTP: Option<IntentionHTTPPermission>,
+}
+
+/// The sources allowed or denied to reach the destination of a `service-intentions` entry.
+/// An L4 source has an `Action`, an L7 source has `Permissions` instead.
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct SourceIntention {
+    /// Source service name, `*` matches all services
+    pub Name: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Peer: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Partition: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Namespace: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub SamenessGroup: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Action: Option<IntentionAction>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub Permissions: Vec<IntentionPermission>,
+    /// Set by Consul
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Precedence: Option<i32>,
+    /// Always `consul`
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Type: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Description: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub LegacyID: String,
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub LegacyMeta: HashMap<String, String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub LegacyCreateTime: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub LegacyUpdateTime: Option<String>,
+}
+
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct ServiceIntentionsConfigEntry {
+    /// Destination service name, `*` matches all services
+    pub Name: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Partition: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Namespace: String,
+    pub Sources: Vec<SourceIntention>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub JWT: Option<Value>,
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub Meta: HashMap<String, String>,
+    pub CreateIndex: u64,
+    pub ModifyIndex: u64,
+    #[serde(flatten)]
+    pub Extra: HashMap<String, Value>,
+}
+
+#[async_trait]
+pub trait ConfigEntries {
+    async fn config_get(
+        &self,
+        kind: &str,
+        name: &str,
+        q: Option<&QueryOptions>,
+    ) -> Result<(ConfigEntry, QueryMeta)>;
+    async fn config_list(
+        &self,
+        kind: &str,
+        q: Option<&QueryOptions>,
+    ) -> Result<(Vec<ConfigEntry>, QueryMeta)>;
+    async fn config_set(
+        &self,
+        entry: &ConfigEntry,
+        cas: Option<u64>,
+        q: Option<&WriteOptions>,
+    ) -> Result<(bool, WriteMeta)>;
+    async fn config_delete(
+        &self,
+        kind: &str,
+        name: &str,
+        q: Option<&WriteOptions>,
+    ) -> Result<((), WriteMeta)>;
+    async fn config_delete_cas(
+        &self,
+        kind: &str,
+        name: &str,
+        index: u64,
+        q: Option<&WriteOptions>,
+    ) -> Result<(bool, WriteMeta)>;
+}
+
+#[async_trait]
+impl ConfigEntries for Client {
+    /// https://developer.hashicorp.com/consul/api-docs/config#get-configuration
+    async fn config_get(
+        &self,
+        kind: &str,
+        name: &str,
+        q: Option<&QueryOptions>,
+    ) -> Result<(ConfigEntry, QueryMeta)> {
+        let path = format!("/v1/config/{}/{}", kind, name);
+        get(&path, &self.config, HashMap::new(), q).await
+    }
+
+    /// https://developer.hashicorp.com/consul/api-docs/config#list-configurations
+    async fn config_list(
+        &self,
+        kind: &str,
+        q: Option<&QueryOptions>,
+    ) -> Result<(Vec<ConfigEntry>, QueryMeta)> {
+        let path = format!("/v1/config/{}", kind);
+        get(&path, &self.config, HashMap::new(), q).await
+    }
+
+    /// https://developer.hashicorp.com/consul/api-docs/config#apply-configuration
+    ///
+    /// With `cas`, the entry is only written if its `ModifyIndex` is still `cas`, use
+    /// `Some(0)` to only create it. Returns whether the entry was written.
+    async fn config_set(
+        &self,
+        entry: &ConfigEntry,
+        cas: Option<u64>,
+        q: Option<&WriteOptions>,
+    ) -> Result<(bool, WriteMeta)> {
+        let mut params = HashMap::new();
+        if let Some(cas) = cas {
+            params.insert(String::from("cas"), cas.to_string());
+        }
+        put("/v1/config", Some(entry), &self.config, params, q).await
+    }
+
+    /// https://developer.hashicorp.com/consul/api-docs/config#delete-configuration
+    async fn config_delete(
+        &self,
+        kind: &str,
+        name: &str,
+        q: Option<&WriteOptions>,
+    ) -> Result<((), WriteMeta)> {
+        let path = format!("/v1/config/{}/{}", kind, name);
+        delete(&path, &self.config, HashMap::new(), q).await
+    }
+
+    /// https://developer.hashicorp.com/consul/api-docs/config#delete-configuration
+    ///
+    /// Only deletes the entry if its `ModifyIndex` is still `index`.
+    async fn config_delete_cas(
+        &self,
+        kind: &str,
+        name: &str,
+        index: u64,
+        q: Option<&WriteOptions>,
+    ) -> Result<(bool, WriteMeta)> {
+        let mut params = HashMap::new();
+        params.insert(String::from("cas"), index.to_string());
+        let path = format!("/v1/config/{}/{}", kind, name);
+        delete(&path, &self.config, params, q).await
+    }
+}
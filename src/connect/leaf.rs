@@ -0,0 +1,329 @@
+use std::convert::TryFrom;
+use std::io::BufRead;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::client::WebPkiServerVerifier;
+use rustls::crypto::CryptoProvider;
+use rustls::pki_types::{CertificateDer, ServerName, UnixTime};
+use rustls::server::WebPkiClientVerifier;
+use rustls::{
+    CertificateError, ClientConfig, DigitallySignedStruct, RootCertStore, ServerConfig,
+    SignatureScheme,
+};
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+
+use crate::connect_ca::{CARootList, ConnectCA, LeafCert};
+use crate::errors::{Error, Result};
+use crate::{Client, QueryOptions};
+
+const MIN_RETRY_DELAY: Duration = Duration::from_secs(1);
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(60);
+const MAX_WAIT_TIME: Duration = Duration::from_secs(300);
+
+/// The TLS material of a service at a point in time.
+#[derive(Debug)]
+pub struct ConnectTls {
+    pub leaf: LeafCert,
+    pub roots: CARootList,
+    /// Requires a client certificate issued by the Connect CA.
+    pub server_config: Arc<ServerConfig>,
+    /// Presents the leaf certificate and only trusts servers with a certificate issued
+    /// by the Connect CA. Server names are not checked, Connect certificates identify
+    /// services by their SPIFFE ID.
+    pub client_config: Arc<ClientConfig>,
+    pub valid_after: SystemTime,
+    pub valid_before: SystemTime,
+}
+
+impl ConnectTls {
+    pub fn new(leaf: LeafCert, roots: CARootList) -> Result<ConnectTls> {
+        let chain = parse_certs(&mut leaf.CertPEM.as_bytes())?;
+        let key = rustls_pemfile::private_key(&mut leaf.PrivateKeyPEM.as_bytes())?
+            .ok_or_else(|| Error::InvalidCertificate(String::from("no private key")))?;
+        let (valid_after, valid_before) = validity(&chain)?;
+
+        let mut store = RootCertStore::empty();
+        for root in &roots.Roots {
+            for cert in parse_certs(&mut root.RootCert.as_bytes())? {
+                store.add(cert)?;
+            }
+        }
+        let store = Arc::new(store);
+        let provider = Arc::new(rustls::crypto::ring::default_provider());
+
+        let client_verifier =
+            WebPkiClientVerifier::builder_with_provider(store.clone(), provider.clone())
+                .build()
+                .map_err(|e| Error::InvalidCertificate(e.to_string()))?;
+        let server_config = ServerConfig::builder_with_provider(provider.clone())
+            .with_safe_default_protocol_versions()?
+            .with_client_cert_verifier(client_verifier)
+            .with_single_cert(chain.clone(), key.clone_key())?;
+
+        let server_verifier = ConnectServerVerifier::new(store, provider.clone())?;
+        let client_config = ClientConfig::builder_with_provider(provider)
+            .with_safe_default_protocol_versions()?
+            .dangerous()
+            .with_custom_certificate_verifier(Arc::new(server_verifier))
+            .with_client_auth_cert(chain, key)?;
+
+        Ok(ConnectTls {
+            leaf,
+            roots,
+            server_config: Arc::new(server_config),
+            client_config: Arc::new(client_config),
+            valid_after,
+            valid_before,
+        })
+    }
+
+    /// When the certificate should be replaced, once 90% of its lifetime has elapsed.
+    /// The agent renews it earlier than that.
+    pub fn renew_at(&self) -> SystemTime {
+        let lifetime = self
+            .valid_before
+            .duration_since(self.valid_after)
+            .unwrap_or_default();
+        self.valid_before - lifetime / 10
+    }
+}
+
+fn parse_certs(pem: &mut dyn BufRead) -> Result<Vec<CertificateDer<'static>>> {
+    let certs = rustls_pemfile::certs(pem).collect::<std::io::Result<Vec<_>>>()?;
+    if certs.is_empty() {
+        return Err(Error::InvalidCertificate(String::from("no certificate")));
+    }
+    Ok(certs)
+}
+
+fn validity(chain: &[CertificateDer<'_>]) -> Result<(SystemTime, SystemTime)> {
+    let (_, cert) = x509_parser::parse_x509_certificate(&chain[0])
+        .map_err(|e| Error::InvalidCertificate(e.to_string()))?;
+    let validity = cert.validity();
+    Ok((
+        to_system_time(validity.not_before)?,
+        to_system_time(validity.not_after)?,
+    ))
+}
+
+fn to_system_time(t: x509_parser::time::ASN1Time) -> Result<SystemTime> {
+    u64::try_from(t.timestamp())
+        .ok()
+        .and_then(|secs| UNIX_EPOCH.checked_add(Duration::from_secs(secs)))
+        .ok_or_else(|| Error::InvalidCertificate(format!("validity out of range: {}", t)))
+}
+
+/// Verifies the server certificate chain against the Connect CA roots, but not its
+/// names.
+#[derive(Debug)]
+struct ConnectServerVerifier {
+    inner: Arc<WebPkiServerVerifier>,
+}
+
+impl ConnectServerVerifier {
+    fn new(roots: Arc<RootCertStore>, provider: Arc<CryptoProvider>) -> Result<Self> {
+        let inner = WebPkiServerVerifier::builder_with_provider(roots, provider)
+            .build()
+            .map_err(|e| Error::InvalidCertificate(e.to_string()))?;
+        Ok(ConnectServerVerifier { inner })
+    }
+}
+
+impl ServerCertVerifier for ConnectServerVerifier {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        intermediates: &[CertificateDer<'_>],
+        server_name: &ServerName<'_>,
+        ocsp_response: &[u8],
+        now: UnixTime,
+    ) -> std::result::Result<ServerCertVerified, rustls::Error> {
+        // The names are checked once the chain is known to be valid
+        match self.inner.verify_server_cert(
+            end_entity,
+            intermediates,
+            server_name,
+            ocsp_response,
+            now,
+        ) {
+            Err(rustls::Error::InvalidCertificate(
+                CertificateError::NotValidForName | CertificateError::NotValidForNameContext { .. },
+            )) => Ok(ServerCertVerified::assertion()),
+            result => result,
+        }
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> std::result::Result<HandshakeSignatureValid, rustls::Error> {
+        self.inner.verify_tls12_signature(message, cert, dss)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> std::result::Result<HandshakeSignatureValid, rustls::Error> {
+        self.inner.verify_tls13_signature(message, cert, dss)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.inner.supported_verify_schemes()
+    }
+}
+
+/// Keeps the leaf certificate of a service and the Connect CA roots up to date.
+///
+/// The certificate is fetched again when the agent renews it, when the CA roots change,
+/// or at the latest before it expires. Each change publishes a new `ConnectTls`, so
+/// connections keep the configuration they were established with.
+pub struct LeafCertManager {
+    rx: watch::Receiver<Arc<ConnectTls>>,
+    tasks: Vec<JoinHandle<()>>,
+}
+
+impl LeafCertManager {
+    pub async fn new(client: Client, service: &str) -> Result<LeafCertManager> {
+        let (leaf, leaf_meta) = client.ca_leaf(service, None).await?;
+        let (roots, roots_meta) = client.ca_roots(None).await?;
+        let tls = ConnectTls::new(leaf, roots)?;
+        let (tx, rx) = watch::channel(Arc::new(tls));
+        let tx = Arc::new(tx);
+        let leaf_watcher = LeafWatcher {
+            client: client.clone(),
+            service: service.to_owned(),
+            tx: tx.clone(),
+        };
+        let roots_watcher = RootsWatcher { client, tx };
+        let tasks = vec![
+            tokio::spawn(leaf_watcher.run(leaf_meta.last_index.unwrap_or(0))),
+            tokio::spawn(roots_watcher.run(roots_meta.last_index.unwrap_or(0))),
+        ];
+        Ok(LeafCertManager { rx, tasks })
+    }
+
+    pub fn current(&self) -> Arc<ConnectTls> {
+        self.rx.borrow().clone()
+    }
+
+    pub fn server_config(&self) -> Arc<ServerConfig> {
+        self.rx.borrow().server_config.clone()
+    }
+
+    pub fn client_config(&self) -> Arc<ClientConfig> {
+        self.rx.borrow().client_config.clone()
+    }
+
+    /// Notified each time the certificate or the CA roots change.
+    pub fn subscribe(&self) -> watch::Receiver<Arc<ConnectTls>> {
+        self.rx.clone()
+    }
+}
+
+impl Drop for LeafCertManager {
+    fn drop(&mut self) {
+        for task in &self.tasks {
+            task.abort();
+        }
+    }
+}
+
+/// Replaces the published material, unless it can't be built from the new certificate
+/// or roots, in which case the previous one is kept.
+fn publish(tx: &watch::Sender<Arc<ConnectTls>>, leaf: Option<LeafCert>, roots: Option<CARootList>) {
+    tx.send_if_modified(|current| {
+        let leaf = leaf.unwrap_or_else(|| current.leaf.clone());
+        let roots = roots.unwrap_or_else(|| current.roots.clone());
+        if leaf == current.leaf && roots == current.roots {
+            return false;
+        }
+        match ConnectTls::new(leaf, roots) {
+            Ok(tls) => {
+                *current = Arc::new(tls);
+                true
+            }
+            Err(_) => false,
+        }
+    });
+}
+
+struct LeafWatcher {
+    client: Client,
+    service: String,
+    tx: Arc<watch::Sender<Arc<ConnectTls>>>,
+}
+
+impl LeafWatcher {
+    async fn run(self, mut index: u64) {
+        let mut retry_delay = MIN_RETRY_DELAY;
+        loop {
+            let renew_at = self.tx.borrow().renew_at();
+            let wait_time = match renew_at.duration_since(SystemTime::now()) {
+                Ok(until_renewal) => {
+                    retry_delay = MIN_RETRY_DELAY;
+                    until_renewal.clamp(MIN_RETRY_DELAY, MAX_WAIT_TIME)
+                }
+                // Past the renewal time, keep asking until the agent hands out a new
+                // certificate
+                Err(_) => {
+                    let delay = retry_delay;
+                    retry_delay = (retry_delay * 2).min(MAX_RETRY_DELAY);
+                    delay
+                }
+            };
+            let options = QueryOptions {
+                wait_index: Some(index),
+                wait_time: Some(wait_time),
+                ..Default::default()
+            };
+            match self.client.ca_leaf(&self.service, Some(&options)).await {
+                Ok((leaf, meta)) => {
+                    let new_index = meta.last_index.unwrap_or(0);
+                    // The index can go backwards, e.g. after a snapshot restore
+                    index = if new_index < index { 0 } else { new_index };
+                    publish(&self.tx, Some(leaf), None);
+                }
+                Err(_) => {
+                    tokio::time::sleep(retry_delay).await;
+                    retry_delay = (retry_delay * 2).min(MAX_RETRY_DELAY);
+                }
+            }
+        }
+    }
+}
+
+struct RootsWatcher {
+    client: Client,
+    tx: Arc<watch::Sender<Arc<ConnectTls>>>,
+}
+
+impl RootsWatcher {
+    async fn run(self, mut index: u64) {
+        let mut retry_delay = MIN_RETRY_DELAY;
+        loop {
+            let options = QueryOptions {
+                wait_index: Some(index),
+                ..Default::default()
+            };
+            match self.client.ca_roots(Some(&options)).await {
+                Ok((roots, meta)) => {
+                    retry_delay = MIN_RETRY_DELAY;
+                    let new_index = meta.last_index.unwrap_or(0);
+                    index = if new_index < index { 0 } else { new_index };
+                    publish(&self.tx, None, Some(roots));
+                }
+                Err(_) => {
+                    tokio::time::sleep(retry_delay).await;
+                    retry_delay = (retry_delay * 2).min(MAX_RETRY_DELAY);
+                }
+            }
+        }
+    }
+}
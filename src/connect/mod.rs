@@ -0,0 +1,5 @@
+//! Connect-native support: services terminating mutual TLS themselves, with certificates
+//! issued by the Connect CA, instead of going through a sidecar proxy.
+
+pub mod leaf;
+pub mod native;
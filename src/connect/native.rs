@@ -0,0 +1,154 @@
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use rand::seq::SliceRandom;
+use rustls::pki_types::{CertificateDer, ServerName};
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::TcpStream;
+use tokio::sync::watch;
+use tokio_rustls::{client, server, TlsAcceptor, TlsConnector};
+
+use crate::agent::Agent;
+use crate::connect::leaf::{ConnectTls, LeafCertManager};
+use crate::discovery::Instance;
+use crate::errors::{Error, Result};
+use crate::health::Health;
+use crate::spiffe::SpiffeId;
+use crate::Client;
+
+/// Accepts mutual TLS connections to a Connect-native service, and only lets through
+/// the clients the intentions allow.
+pub struct ConnectAcceptor {
+    client: Client,
+    rx: watch::Receiver<Arc<ConnectTls>>,
+}
+
+impl ConnectAcceptor {
+    pub fn new(client: Client, certs: &LeafCertManager) -> ConnectAcceptor {
+        ConnectAcceptor {
+            client,
+            rx: certs.subscribe(),
+        }
+    }
+
+    /// Completes the handshake with the current certificate and CA roots, then asks the
+    /// agent whether the client may connect. Returns the stream and the identity of the
+    /// client.
+    pub async fn accept<IO>(&self, stream: IO) -> Result<(server::TlsStream<IO>, SpiffeId)>
+    where
+        IO: AsyncRead + AsyncWrite + Unpin,
+    {
+        let tls = self.rx.borrow().clone();
+        let stream = TlsAcceptor::from(tls.server_config.clone())
+            .accept(stream)
+            .await?;
+        let (_, connection) = stream.get_ref();
+        let cert = connection
+            .peer_certificates()
+            .and_then(|certs| certs.first())
+            .ok_or_else(|| Error::InvalidCertificate(String::from("no client certificate")))?;
+        let id = SpiffeId::from_certificate(cert)?;
+        id.validate(&tls.roots)?;
+        let authorize = self
+            .client
+            .connect_authorize(&tls.leaf.Service, &id.to_string(), &serial_number(cert)?)
+            .await?;
+        if !authorize.Authorized {
+            return Err(Error::Unauthorized(authorize.Reason));
+        }
+        Ok((stream, id))
+    }
+}
+
+/// Opens mutual TLS connections to the healthy Connect-capable instances of a service.
+pub struct ConnectConnector {
+    client: Client,
+    rx: watch::Receiver<Arc<ConnectTls>>,
+}
+
+impl ConnectConnector {
+    pub fn new(client: Client, certs: &LeafCertManager) -> ConnectConnector {
+        ConnectConnector {
+            client,
+            rx: certs.subscribe(),
+        }
+    }
+
+    /// Dials a random healthy instance of `service`. The connection is rejected unless
+    /// the server presents the identity of `service`.
+    pub async fn connect(&self, service: &str) -> Result<client::TlsStream<TcpStream>> {
+        let (entries, _) = self.client.connect(service, None, true, None).await?;
+        let instances: Vec<Instance> = entries.iter().filter_map(Instance::from_entry).collect();
+        let instance = instances
+            .choose(&mut rand::thread_rng())
+            .ok_or_else(|| Error::NoInstance(service.to_owned()))?;
+        self.connect_to(service, instance.address).await
+    }
+
+    /// Connects to `address`, expecting it to be an instance of `service` in the
+    /// namespace, partition and datacenter of the client.
+    pub async fn connect_to(
+        &self,
+        service: &str,
+        address: SocketAddr,
+    ) -> Result<client::TlsStream<TcpStream>> {
+        let tls = self.rx.borrow().clone();
+        let stream = TcpStream::connect(address).await?;
+        let stream = TlsConnector::from(tls.client_config.clone())
+            .connect(ServerName::IpAddress(address.ip().into()), stream)
+            .await?;
+        let (_, connection) = stream.get_ref();
+        let cert = connection
+            .peer_certificates()
+            .and_then(|certs| certs.first())
+            .ok_or_else(|| Error::InvalidCertificate(String::from("no server certificate")))?;
+        let id = SpiffeId::from_certificate(cert)?;
+        let expected = self.expected_id(&tls, service)?;
+        if !id.matches(&expected) {
+            return Err(Error::InvalidSpiffeId(format!(
+                "{} is not the identity of {}",
+                id, expected
+            )));
+        }
+        Ok(stream)
+    }
+
+    /// The identity of `service` in the scope of the client, the datacenter defaults to
+    /// the one of the local leaf certificate.
+    fn expected_id(&self, tls: &ConnectTls, service: &str) -> Result<SpiffeId> {
+        let config = &self.client.config;
+        let datacenter = match &config.datacenter {
+            Some(datacenter) => datacenter.clone(),
+            None => tls
+                .leaf
+                .ServiceURI
+                .parse::<SpiffeId>()?
+                .datacenter()
+                .to_owned(),
+        };
+        Ok(SpiffeId::Service {
+            trust_domain: tls.roots.TrustDomain.clone(),
+            partition: config.partition.clone(),
+            namespace: config
+                .namespace
+                .clone()
+                .unwrap_or_else(|| String::from("default")),
+            datacenter,
+            service: service.to_owned(),
+        })
+    }
+}
+
+/// The serial number of a certificate, formatted like Consul does (`LeafCert::SerialNumber`):
+/// colon-separated hex bytes of the number, without the DER sign byte.
+pub fn serial_number(cert: &CertificateDer<'_>) -> Result<String> {
+    let (_, cert) = x509_parser::parse_x509_certificate(cert)
+        .map_err(|e| Error::InvalidCertificate(e.to_string()))?;
+    Ok(cert
+        .raw_serial()
+        .iter()
+        .skip_while(|b| **b == 0)
+        .map(|b| format!("{:02x}", b))
+        .collect::<Vec<_>>()
+        .join(":"))
+}
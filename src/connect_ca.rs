@@ -1,41 +1,278 @@
 use async_trait::async_trait;
 use std::collections::HashMap;
+use std::future::Future;
+use std::time::{Duration, Instant};
 
-use crate::errors::Result;
+use crate::errors::{Error, Result};
 use crate::request::{get, put};
 use crate::{Client, QueryMeta, QueryOptions, WriteMeta, WriteOptions};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
-#[derive(Default, Serialize, Deserialize, Debug)]
-#[serde(default)]
+/// The configuration of the Connect CA. `Config` determines the provider.
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(from = "RawCAConfig", into = "RawCAConfig")]
 #[allow(clippy::upper_case_acronyms)]
 pub struct CAConfig {
+    pub Config: CAProviderConfig,
+    /// Allows rotating to a root that can't be cross-signed by the current one, leaf
+    /// certificates issued by the current root are rejected until they are renewed.
+    pub ForceWithoutCrossSigning: bool,
+    pub CreateIndex: u64,
+    pub ModifyIndex: u64,
+}
+
+impl CAConfig {
+    pub fn new(config: CAProviderConfig) -> Self {
+        CAConfig {
+            Config: config,
+            ..Default::default()
+        }
+    }
+
+    pub fn provider(&self) -> &str {
+        self.Config.provider()
+    }
+}
+
+/// `CAConfig` as sent over the wire, the shape of `Config` depends on `Provider`.
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+struct RawCAConfig {
     Provider: String,
     Config: Value,
+    ForceWithoutCrossSigning: bool,
     CreateIndex: u64,
     ModifyIndex: u64,
 }
 
-#[derive(Default, Serialize, Deserialize, Debug)]
+impl From<RawCAConfig> for CAConfig {
+    fn from(raw: RawCAConfig) -> Self {
+        CAConfig {
+            Config: CAProviderConfig::from_raw(raw.Provider, raw.Config),
+            ForceWithoutCrossSigning: raw.ForceWithoutCrossSigning,
+            CreateIndex: raw.CreateIndex,
+            ModifyIndex: raw.ModifyIndex,
+        }
+    }
+}
+
+impl From<CAConfig> for RawCAConfig {
+    fn from(config: CAConfig) -> Self {
+        let (provider, value) = config.Config.into_raw();
+        RawCAConfig {
+            Provider: provider,
+            Config: value,
+            ForceWithoutCrossSigning: config.ForceWithoutCrossSigning,
+            CreateIndex: config.CreateIndex,
+            ModifyIndex: config.ModifyIndex,
+        }
+    }
+}
+
+/// The configuration of a CA provider. A configuration that doesn't match the type of
+/// its provider, or of a provider this crate doesn't know about, is kept as `Raw`.
+#[derive(Clone, PartialEq, Debug)]
+#[allow(clippy::large_enum_variant)]
+pub enum CAProviderConfig {
+    Consul(ConsulCAProviderConfig),
+    Vault(VaultCAProviderConfig),
+    AwsPca(AwsPcaCAProviderConfig),
+    Raw { provider: String, config: Value },
+}
+
+impl Default for CAProviderConfig {
+    fn default() -> Self {
+        CAProviderConfig::Consul(ConsulCAProviderConfig::default())
+    }
+}
+
+impl CAProviderConfig {
+    pub fn provider(&self) -> &str {
+        match self {
+            CAProviderConfig::Consul(_) => "consul",
+            CAProviderConfig::Vault(_) => "vault",
+            CAProviderConfig::AwsPca(_) => "aws-pca",
+            CAProviderConfig::Raw { provider, .. } => provider,
+        }
+    }
+
+    fn from_raw(provider: String, config: Value) -> Self {
+        let typed = match provider.as_str() {
+            "consul" => serde_json::from_value(config.clone()).map(CAProviderConfig::Consul),
+            "vault" => serde_json::from_value(config.clone()).map(CAProviderConfig::Vault),
+            "aws-pca" => serde_json::from_value(config.clone()).map(CAProviderConfig::AwsPca),
+            _ => return CAProviderConfig::Raw { provider, config },
+        };
+        typed.unwrap_or(CAProviderConfig::Raw { provider, config })
+    }
+
+    fn into_raw(self) -> (String, Value) {
+        let provider = self.provider().to_owned();
+        // Serializing maps with string keys can't fail
+        let config = match self {
+            CAProviderConfig::Consul(c) => serde_json::to_value(c).unwrap(),
+            CAProviderConfig::Vault(c) => serde_json::to_value(c).unwrap(),
+            CAProviderConfig::AwsPca(c) => serde_json::to_value(c).unwrap(),
+            CAProviderConfig::Raw { config, .. } => config,
+        };
+        (provider, config)
+    }
+}
+
+/// https://developer.hashicorp.com/consul/docs/connect/ca/consul
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct ConsulCAProviderConfig {
+    /// PEM encoded private key of the root, generated when empty
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub PrivateKey: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub RootCert: String,
+    /// Duration, e.g. `72h`
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub LeafCertTTL: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub IntermediateCertTTL: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub RootCertTTL: String,
+    /// `ec` or `rsa`
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub PrivateKeyType: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub PrivateKeyBits: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub CSRMaxPerSecond: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub CSRMaxConcurrent: Option<u32>,
+    #[serde(flatten)]
+    pub Extra: HashMap<String, Value>,
+}
+
+/// https://developer.hashicorp.com/consul/docs/connect/ca/vault
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct VaultCAProviderConfig {
+    pub Address: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Token: String,
+    /// Vault auth method, used instead of `Token`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub AuthMethod: Option<Value>,
+    pub RootPKIPath: String,
+    pub IntermediatePKIPath: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Namespace: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub RootPKINamespace: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub IntermediatePKINamespace: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub CAFile: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub CAPath: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub CertFile: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub KeyFile: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub TLSServerName: String,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub TLSSkipVerify: bool,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub LeafCertTTL: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub IntermediateCertTTL: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub RootCertTTL: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub PrivateKeyType: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub PrivateKeyBits: Option<u32>,
+    #[serde(flatten)]
+    pub Extra: HashMap<String, Value>,
+}
+
+/// https://developer.hashicorp.com/consul/docs/connect/ca/aws
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct AwsPcaCAProviderConfig {
+    /// ARN of an existing private CA, one is created when empty
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub ExistingARN: String,
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub DeleteOnExit: bool,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub LeafCertTTL: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub PrivateKeyType: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub PrivateKeyBits: Option<u32>,
+    #[serde(flatten)]
+    pub Extra: HashMap<String, Value>,
+}
+
+#[derive(Clone, Eq, Default, PartialEq, Serialize, Deserialize, Debug)]
 #[serde(default)]
 #[allow(clippy::upper_case_acronyms)]
 pub struct CARootList {
-    ActiveRootID: String,
-    TrustDomain: String,
-    Roots: Vec<CARoot>,
+    pub ActiveRootID: String,
+    pub TrustDomain: String,
+    pub Roots: Vec<CARoot>,
 }
 
-#[derive(Eq, Default, PartialEq, Serialize, Deserialize, Debug)]
+impl CARootList {
+    pub fn active_root(&self) -> Option<&CARoot> {
+        self.Roots.iter().find(|root| root.Active)
+    }
+}
+
+#[derive(Clone, Eq, Default, PartialEq, Serialize, Deserialize, Debug)]
 #[serde(default)]
 #[allow(clippy::upper_case_acronyms)]
 pub struct CARoot {
-    ID: String,
-    Name: String,
-    RootCert: String,
-    Active: bool,
-    CreateIndex: u64,
-    ModifyIndex: u64,
+    pub ID: String,
+    pub Name: String,
+    pub SerialNumber: u64,
+    pub SigningKeyID: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub ExternalTrustDomain: String,
+    /// RFC 3339 timestamp
+    pub NotBefore: String,
+    /// RFC 3339 timestamp
+    pub NotAfter: String,
+    pub RootCert: String,
+    /// Intermediates to present along with the leaf certificates, including the
+    /// cross-signed certificate of a root after a rotation
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub IntermediateCerts: Vec<String>,
+    pub Active: bool,
+    /// RFC 3339 timestamp, when a root was replaced by another one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub RotatedOutAt: Option<String>,
+    pub PrivateKeyType: String,
+    pub PrivateKeyBits: u32,
+    pub CreateIndex: u64,
+    pub ModifyIndex: u64,
+}
+
+/// A leaf certificate issued to a service by the Connect CA.
+#[derive(Clone, Eq, Default, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct LeafCert {
+    pub SerialNumber: String,
+    /// The certificate, followed by the intermediates if any
+    pub CertPEM: String,
+    pub PrivateKeyPEM: String,
+    pub Service: String,
+    /// The SPIFFE ID of the service
+    pub ServiceURI: String,
+    /// RFC 3339 timestamp
+    pub ValidAfter: String,
+    /// RFC 3339 timestamp
+    pub ValidBefore: String,
+    pub CreateIndex: u64,
+    pub ModifyIndex: u64,
 }
 
 #[allow(clippy::upper_case_acronyms)]
@@ -48,6 +285,12 @@ pub trait ConnectCA {
         conf: &CAConfig,
         q: Option<&WriteOptions>,
     ) -> Result<((), WriteMeta)>;
+    async fn ca_leaf(
+        &self,
+        service: &str,
+        q: Option<&QueryOptions>,
+    ) -> Result<(LeafCert, QueryMeta)>;
+    async fn ca_rotate(&self, config: &CAConfig, timeout: Duration) -> Result<CARootList>;
 }
 
 #[async_trait]
@@ -83,4 +326,90 @@ impl ConnectCA for Client {
         )
         .await
     }
+
+    /// https://developer.hashicorp.com/consul/api-docs/agent/connect#service-leaf-certificate
+    ///
+    /// The agent caches the certificate and renews it before it expires, a blocking
+    /// query returns as soon as it does.
+    async fn ca_leaf(
+        &self,
+        service: &str,
+        q: Option<&QueryOptions>,
+    ) -> Result<(LeafCert, QueryMeta)> {
+        let path = format!("/v1/agent/connect/ca/leaf/{}", service);
+        get(&path, &self.config, HashMap::new(), q).await
+    }
+
+    /// Applies a new CA configuration, then waits for Consul to switch to the new root.
+    ///
+    /// Completes once a root other than the current one is active and the current one has
+    /// been retired, its cross-signed certificate being in the `IntermediateCerts` of the new
+    /// root unless `ForceWithoutCrossSigning` is set. Returns the roots at that point, or
+    /// fails with `Error::CARotationIncomplete` after `timeout`.
+    async fn ca_rotate(&self, config: &CAConfig, timeout: Duration) -> Result<CARootList> {
+        let deadline = Instant::now() + timeout;
+        let (roots, meta) = before(deadline, "CA roots not read", self.ca_roots(None)).await?;
+        let old_root = roots.active_root().cloned().unwrap_or_default();
+        let cross_signed = !config.ForceWithoutCrossSigning;
+        before(
+            deadline,
+            "CA configuration not applied",
+            self.ca_set_config(config, None),
+        )
+        .await?;
+        let mut missing = format!("root {} is still active", old_root.ID);
+        let mut index = meta.last_index.unwrap_or(0);
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() {
+                return Err(Error::CARotationIncomplete(missing));
+            }
+            let options = QueryOptions {
+                wait_index: Some(index),
+                wait_time: Some(remaining),
+                ..Default::default()
+            };
+            let (roots, meta) = before(deadline, &missing, self.ca_roots(Some(&options))).await?;
+            match rotation_pending(&roots, &old_root, cross_signed) {
+                Some(reason) => missing = reason,
+                None => return Ok(roots),
+            }
+            let new_index = meta.last_index.unwrap_or(0);
+            index = if new_index < index { 0 } else { new_index };
+        }
+    }
+}
+
+/// Describes what `roots` still lacks for the rotation away from `old_root` to be complete.
+fn rotation_pending(roots: &CARootList, old_root: &CARoot, cross_signed: bool) -> Option<String> {
+    let new_root = match roots.active_root() {
+        Some(root) if root.ID != old_root.ID => root,
+        _ => return Some(format!("root {} is still active", old_root.ID)),
+    };
+    let retired = !roots
+        .Roots
+        .iter()
+        .any(|root| root.ID == old_root.ID && (root.Active || root.RotatedOutAt.is_none()));
+    if !retired {
+        return Some(format!("root {} is not retired", old_root.ID));
+    }
+    if cross_signed && new_root.IntermediateCerts.is_empty() {
+        return Some(format!(
+            "root {} has no cross-signed intermediate",
+            new_root.ID
+        ));
+    }
+    None
+}
+
+/// Awaits `f`, failing with `Error::CARotationIncomplete` past `deadline`.
+async fn before<T>(
+    deadline: Instant,
+    reason: &str,
+    f: impl Future<Output = Result<T>>,
+) -> Result<T> {
+    let remaining = deadline.saturating_duration_since(Instant::now());
+    tokio::time::timeout(remaining, f)
+        .await
+        .map_err(|_| Error::CARotationIncomplete(reason.to_owned()))?
 }
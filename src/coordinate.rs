@@ -0,0 +1,166 @@
+use async_trait::async_trait;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::time::Duration;
+
+use crate::errors::Result;
+use crate::health::ServiceEntry;
+use crate::request::{get, put};
+use crate::{Client, QueryMeta, QueryOptions, WriteMeta, WriteOptions};
+use serde::{Deserialize, Serialize};
+
+/// A Vivaldi network coordinate, distances are in seconds.
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct Coordinate {
+    pub Vec: Vec<f64>,
+    pub Error: f64,
+    pub Adjustment: f64,
+    pub Height: f64,
+}
+
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct CoordinateEntry {
+    pub Node: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Segment: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Partition: String,
+    pub Coord: Coordinate,
+}
+
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct CoordinateDatacenterMap {
+    pub Datacenter: String,
+    pub AreaID: String,
+    pub Coordinates: Vec<CoordinateEntry>,
+}
+
+/// Estimated round trip time between two coordinates, computed like Consul does.
+/// Returns `None` if the coordinates don't have the same dimensionality.
+pub fn distance_rtt(a: &Coordinate, b: &Coordinate) -> Option<Duration> {
+    if a.Vec.len() != b.Vec.len() {
+        return None;
+    }
+    let magnitude = a
+        .Vec
+        .iter()
+        .zip(&b.Vec)
+        .map(|(x, y)| (x - y) * (x - y))
+        .sum::<f64>()
+        .sqrt();
+    let dist = magnitude + a.Height + b.Height;
+    let adjusted = dist + a.Adjustment + b.Adjustment;
+    let seconds = if adjusted > 0.0 { adjusted } else { dist };
+    Some(Duration::from_secs_f64(seconds.max(0.0)))
+}
+
+/// Sorts service entries by estimated round trip time from `origin`, using the
+/// coordinates of their nodes. Entries without a usable coordinate come last.
+pub fn rank_by_rtt<'a>(
+    origin: &Coordinate,
+    entries: &'a [ServiceEntry],
+    coordinates: &[CoordinateEntry],
+) -> Vec<(&'a ServiceEntry, Option<Duration>)> {
+    let by_node: HashMap<&str, &Coordinate> = coordinates
+        .iter()
+        .map(|c| (c.Node.as_str(), &c.Coord))
+        .collect();
+    let mut ranked: Vec<_> = entries
+        .iter()
+        .map(|entry| {
+            let rtt = by_node
+                .get(entry.Node.Node.as_str())
+                .and_then(|coord| distance_rtt(origin, coord));
+            (entry, rtt)
+        })
+        .collect();
+    ranked.sort_by(|(_, a), (_, b)| match (a, b) {
+        (Some(a), Some(b)) => a.cmp(b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    });
+    ranked
+}
+
+#[async_trait]
+pub trait Coordinates {
+    async fn coordinate_datacenters(
+        &self,
+        q: Option<&QueryOptions>,
+    ) -> Result<(Vec<CoordinateDatacenterMap>, QueryMeta)>;
+    async fn coordinate_nodes(
+        &self,
+        segment: Option<&str>,
+        q: Option<&QueryOptions>,
+    ) -> Result<(Vec<CoordinateEntry>, QueryMeta)>;
+    async fn coordinate_node(
+        &self,
+        node: &str,
+        q: Option<&QueryOptions>,
+    ) -> Result<(Vec<CoordinateEntry>, QueryMeta)>;
+    async fn coordinate_update(
+        &self,
+        entry: &CoordinateEntry,
+        q: Option<&WriteOptions>,
+    ) -> Result<((), WriteMeta)>;
+}
+
+#[async_trait]
+impl Coordinates for Client {
+    /// https://developer.hashicorp.com/consul/api-docs/coordinate#read-wan-coordinates
+    async fn coordinate_datacenters(
+        &self,
+        q: Option<&QueryOptions>,
+    ) -> Result<(Vec<CoordinateDatacenterMap>, QueryMeta)> {
+        get(
+            "/v1/coordinate/datacenters",
+            &self.config,
+            HashMap::new(),
+            q,
+        )
+        .await
+    }
+
+    /// https://developer.hashicorp.com/consul/api-docs/coordinate#read-lan-coordinates-for-all-nodes
+    async fn coordinate_nodes(
+        &self,
+        segment: Option<&str>,
+        q: Option<&QueryOptions>,
+    ) -> Result<(Vec<CoordinateEntry>, QueryMeta)> {
+        let mut params = HashMap::new();
+        if let Some(segment) = segment {
+            params.insert(String::from("segment"), segment.to_owned());
+        }
+        get("/v1/coordinate/nodes", &self.config, params, q).await
+    }
+
+    /// https://developer.hashicorp.com/consul/api-docs/coordinate#read-lan-coordinates-for-a-node
+    async fn coordinate_node(
+        &self,
+        node: &str,
+        q: Option<&QueryOptions>,
+    ) -> Result<(Vec<CoordinateEntry>, QueryMeta)> {
+        let path = format!("/v1/coordinate/node/{}", node);
+        get(&path, &self.config, HashMap::new(), q).await
+    }
+
+    /// https://developer.hashicorp.com/consul/api-docs/coordinate#update-lan-coordinates-for-a-node
+    async fn coordinate_update(
+        &self,
+        entry: &CoordinateEntry,
+        q: Option<&WriteOptions>,
+    ) -> Result<((), WriteMeta)> {
+        put(
+            "/v1/coordinate/update",
+            Some(entry),
+            &self.config,
+            HashMap::new(),
+            q,
+        )
+        .await
+    }
+}
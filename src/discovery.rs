@@ -0,0 +1,393 @@
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "tower")]
+use std::collections::VecDeque;
+#[cfg(feature = "tower")]
+use std::convert::Infallible;
+#[cfg(feature = "tower")]
+use std::pin::Pin;
+#[cfg(feature = "tower")]
+use std::task::{Context, Poll};
+
+#[cfg(feature = "tower")]
+use futures_core::Stream;
+use rand::Rng;
+use tokio::sync::watch;
+use tokio::task::JoinHandle;
+#[cfg(feature = "tower")]
+use tokio_stream::wrappers::WatchStream;
+#[cfg(feature = "tower")]
+use tower::discover::Change;
+
+use crate::agent::AgentWeights;
+use crate::errors::Result;
+use crate::health::{Health, ServiceEntry};
+use crate::{Client, QueryOptions};
+
+const MIN_RETRY_DELAY: Duration = Duration::from_secs(1);
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(60);
+
+/// A healthy instance of a service, as seen by `ServiceDiscovery`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Instance {
+    pub node: String,
+    pub service_id: String,
+    /// The service address, or the node address when the service has none.
+    pub address: SocketAddr,
+    pub tags: Vec<String>,
+    pub meta: HashMap<String, String>,
+    pub weights: AgentWeights,
+    /// Whether at least one check of the instance is in the `warning` state.
+    pub warning: bool,
+}
+
+impl Instance {
+    /// Identifies the instance in the cluster, service IDs are only unique per node.
+    pub fn key(&self) -> String {
+        format!("{}/{}", self.node, self.service_id)
+    }
+
+    /// The weight of the instance given its current state.
+    pub fn weight(&self) -> u32 {
+        let weight = if self.warning {
+            self.weights.Warning
+        } else {
+            self.weights.Passing
+        };
+        weight.max(0) as u32
+    }
+
+    /// Converts a health entry, skipping critical instances and instances
+    /// without an IP address.
+    pub fn from_entry(entry: &ServiceEntry) -> Option<Instance> {
+        if entry.Checks.iter().any(|c| c.Status == "critical") {
+            return None;
+        }
+        let address = if entry.Service.Address.is_empty() {
+            &entry.Node.Address
+        } else {
+            &entry.Service.Address
+        };
+        let ip: IpAddr = address.parse().ok()?;
+        Some(Instance {
+            node: entry.Node.Node.clone(),
+            service_id: entry.Service.ID.clone(),
+            address: SocketAddr::new(ip, entry.Service.Port),
+            tags: entry.Service.Tags.clone().unwrap_or_default(),
+            meta: entry.Service.Meta.clone().unwrap_or_default(),
+            weights: entry.Service.Weights.unwrap_or(AgentWeights {
+                Passing: 1,
+                Warning: 1,
+            }),
+            warning: entry.Checks.iter().any(|c| c.Status == "warning"),
+        })
+    }
+}
+
+/// Picks one instance among the current healthy set.
+pub trait Selector: Send + Sync {
+    fn select<'a>(&self, instances: &'a [Instance]) -> Option<&'a Instance>;
+}
+
+#[derive(Debug, Default)]
+pub struct RoundRobin {
+    next: AtomicUsize,
+}
+
+impl Selector for RoundRobin {
+    fn select<'a>(&self, instances: &'a [Instance]) -> Option<&'a Instance> {
+        if instances.is_empty() {
+            return None;
+        }
+        let next = self.next.fetch_add(1, Ordering::Relaxed);
+        instances.get(next % instances.len())
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Random;
+
+impl Selector for Random {
+    fn select<'a>(&self, instances: &'a [Instance]) -> Option<&'a Instance> {
+        if instances.is_empty() {
+            return None;
+        }
+        instances.get(rand::thread_rng().gen_range(0..instances.len()))
+    }
+}
+
+/// Random selection proportional to `Weights.Passing`, or `Weights.Warning`
+/// for instances with a warning check.
+#[derive(Debug, Default)]
+pub struct Weighted;
+
+impl Selector for Weighted {
+    fn select<'a>(&self, instances: &'a [Instance]) -> Option<&'a Instance> {
+        let total: u64 = instances.iter().map(|i| u64::from(i.weight())).sum();
+        if total == 0 {
+            return None;
+        }
+        let mut pick = rand::thread_rng().gen_range(0..total);
+        for instance in instances {
+            let weight = u64::from(instance.weight());
+            if pick < weight {
+                return Some(instance);
+            }
+            pick -= weight;
+        }
+        None
+    }
+}
+
+/// Picks the instance that was returned the longest time ago, new instances first.
+#[derive(Debug, Default)]
+pub struct LeastRecentlyUsed {
+    last_used: Mutex<HashMap<String, Instant>>,
+}
+
+impl Selector for LeastRecentlyUsed {
+    fn select<'a>(&self, instances: &'a [Instance]) -> Option<&'a Instance> {
+        let mut last_used = self.last_used.lock().unwrap();
+        let selected = instances
+            .iter()
+            .min_by_key(|i| last_used.get(&i.key()).copied())?;
+        let now = Instant::now();
+        last_used.insert(selected.key(), now);
+        // Forget instances that left the set
+        last_used.retain(|key, _| instances.iter().any(|i| &i.key() == key));
+        Some(selected)
+    }
+}
+
+pub struct ServiceDiscoveryBuilder {
+    client: Client,
+    service: String,
+    tag: Option<String>,
+    passing_only: bool,
+    selector: Box<dyn Selector>,
+    options: QueryOptions,
+}
+
+impl ServiceDiscoveryBuilder {
+    pub fn tag(mut self, tag: &str) -> Self {
+        self.tag = Some(tag.to_owned());
+        self
+    }
+
+    /// Excludes instances with a warning check, they are kept by default.
+    pub fn passing_only(mut self, passing_only: bool) -> Self {
+        self.passing_only = passing_only;
+        self
+    }
+
+    pub fn selector<S: Selector + 'static>(mut self, selector: S) -> Self {
+        self.selector = Box::new(selector);
+        self
+    }
+
+    /// Options of the blocking queries, `wait_index` is managed by the watcher.
+    pub fn query_options(mut self, options: QueryOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Fetches the initial set of instances and starts watching for changes.
+    pub async fn build(self) -> Result<ServiceDiscovery> {
+        let mut watcher = Watcher {
+            client: self.client,
+            service: self.service,
+            tag: self.tag,
+            passing_only: self.passing_only,
+            options: self.options,
+        };
+        let (instances, index) = watcher.fetch().await?;
+        let (tx, rx) = watch::channel(Arc::new(instances));
+        let task = tokio::spawn(watcher.run(tx, index));
+        Ok(ServiceDiscovery {
+            rx,
+            selector: self.selector,
+            task,
+        })
+    }
+}
+
+/// A live set of healthy instances of a service, kept up to date with blocking
+/// queries on `Health::service`.
+pub struct ServiceDiscovery {
+    rx: watch::Receiver<Arc<Vec<Instance>>>,
+    selector: Box<dyn Selector>,
+    task: JoinHandle<()>,
+}
+
+impl ServiceDiscovery {
+    /// Uses `RoundRobin` unless another selector is given.
+    pub fn builder(client: Client, service: &str) -> ServiceDiscoveryBuilder {
+        ServiceDiscoveryBuilder {
+            client,
+            service: service.to_owned(),
+            tag: None,
+            passing_only: false,
+            selector: Box::new(RoundRobin::default()),
+            options: QueryOptions::default(),
+        }
+    }
+
+    pub fn instances(&self) -> Arc<Vec<Instance>> {
+        self.rx.borrow().clone()
+    }
+
+    /// A receiver notified every time the set of instances changes.
+    pub fn subscribe(&self) -> watch::Receiver<Arc<Vec<Instance>>> {
+        self.rx.clone()
+    }
+
+    pub fn select_instance(&self) -> Option<Instance> {
+        let instances = self.instances();
+        self.selector.select(&instances).cloned()
+    }
+
+    pub fn select(&self) -> Option<SocketAddr> {
+        self.select_instance().map(|i| i.address)
+    }
+}
+
+#[cfg(feature = "tower")]
+impl ServiceDiscovery {
+    /// A `tower::discover::Discover` over the instances, `make` builds the service
+    /// used to reach a new instance.
+    pub fn discover<F, S>(&self, make: F) -> DiscoverInstances<F, S>
+    where
+        F: FnMut(&Instance) -> S,
+    {
+        DiscoverInstances {
+            updates: WatchStream::new(self.subscribe()),
+            known: HashMap::new(),
+            pending: VecDeque::new(),
+            make,
+        }
+    }
+}
+
+impl Drop for ServiceDiscovery {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+struct Watcher {
+    client: Client,
+    service: String,
+    tag: Option<String>,
+    passing_only: bool,
+    options: QueryOptions,
+}
+
+impl Watcher {
+    async fn fetch(&mut self) -> Result<(Vec<Instance>, u64)> {
+        let (entries, meta) = self
+            .client
+            .service(
+                &self.service,
+                self.tag.as_deref(),
+                self.passing_only,
+                Some(&self.options),
+            )
+            .await?;
+        let instances = entries.iter().filter_map(Instance::from_entry).collect();
+        Ok((instances, meta.last_index.unwrap_or(0)))
+    }
+
+    async fn run(mut self, tx: watch::Sender<Arc<Vec<Instance>>>, index: u64) {
+        let mut retry_delay = MIN_RETRY_DELAY;
+        // Like Consul's own watches, the index is kept above 0 as a query with an index
+        // of 0 doesn't block
+        let mut index = index.max(1);
+        loop {
+            self.options.wait_index = Some(index);
+            match self.fetch().await {
+                Ok((instances, new_index)) => {
+                    retry_delay = MIN_RETRY_DELAY;
+                    // The index can go backwards, e.g. after a snapshot restore
+                    index = if new_index < index { 1 } else { new_index };
+                    if **tx.borrow() != instances && tx.send(Arc::new(instances)).is_err() {
+                        return;
+                    }
+                    // Without an index the queries don't block
+                    if new_index == 0 {
+                        tokio::time::sleep(MIN_RETRY_DELAY).await;
+                    }
+                }
+                Err(_) => {
+                    tokio::time::sleep(retry_delay).await;
+                    retry_delay = (retry_delay * 2).min(MAX_RETRY_DELAY);
+                }
+            }
+        }
+    }
+}
+
+/// Emits `Change::Insert` and `Change::Remove` keyed by `Instance::key` as instances
+/// come and go. An instance whose address or weights changed is removed and inserted again.
+#[cfg(feature = "tower")]
+pub struct DiscoverInstances<F, S> {
+    updates: WatchStream<Arc<Vec<Instance>>>,
+    known: HashMap<String, Instance>,
+    pending: VecDeque<Change<String, S>>,
+    make: F,
+}
+
+#[cfg(feature = "tower")]
+impl<F, S> DiscoverInstances<F, S>
+where
+    F: FnMut(&Instance) -> S,
+{
+    fn update(&mut self, instances: &[Instance]) {
+        let current: HashMap<String, &Instance> = instances.iter().map(|i| (i.key(), i)).collect();
+        let known = std::mem::take(&mut self.known);
+        for (key, instance) in known {
+            match current.get(&key) {
+                Some(new) if **new == instance => {
+                    self.known.insert(key, instance);
+                }
+                _ => self.pending.push_back(Change::Remove(key)),
+            }
+        }
+        for (key, instance) in current {
+            if !self.known.contains_key(&key) {
+                self.pending
+                    .push_back(Change::Insert(key.clone(), (self.make)(instance)));
+                self.known.insert(key, instance.clone());
+            }
+        }
+    }
+}
+
+// Pending services are never pinned, they are moved out of the queue as they are emitted.
+#[cfg(feature = "tower")]
+impl<F: Unpin, S> Unpin for DiscoverInstances<F, S> {}
+
+#[cfg(feature = "tower")]
+impl<F, S> Stream for DiscoverInstances<F, S>
+where
+    F: FnMut(&Instance) -> S + Unpin,
+{
+    type Item = std::result::Result<Change<String, S>, Infallible>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        loop {
+            if let Some(change) = this.pending.pop_front() {
+                return Poll::Ready(Some(Ok(change)));
+            }
+            match Pin::new(&mut this.updates).poll_next(cx) {
+                Poll::Ready(Some(instances)) => this.update(&instances),
+                Poll::Ready(None) => return Poll::Ready(None),
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
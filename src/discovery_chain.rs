@@ -0,0 +1,188 @@
+use async_trait::async_trait;
+use std::collections::HashMap;
+
+use crate::agent::MeshGatewayConfig;
+use crate::config_entry::{LoadBalancer, ServiceResolverSubset, ServiceRoute, ServiceSplit};
+use crate::errors::Result;
+use crate::request::{get, post_query};
+use crate::{Client, QueryMeta, QueryOptions};
+use serde::{Deserialize, Serialize};
+
+/// Compiles the chain as seen from another datacenter, or with overridden defaults.
+#[derive(Clone, Default, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct DiscoveryChainOptions {
+    /// Datacenter the chain is compiled for, instead of the local one
+    #[serde(skip)]
+    pub EvaluateInDatacenter: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub OverrideMeshGateway: Option<MeshGatewayConfig>,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub OverrideProtocol: String,
+    /// Duration, e.g. `5s`
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub OverrideConnectTimeout: String,
+}
+
+impl DiscoveryChainOptions {
+    fn has_overrides(&self) -> bool {
+        self.OverrideMeshGateway.is_some()
+            || !self.OverrideProtocol.is_empty()
+            || !self.OverrideConnectTimeout.is_empty()
+    }
+}
+
+#[derive(Clone, Copy, Eq, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(rename_all = "lowercase")]
+pub enum DiscoveryGraphNodeType {
+    Router,
+    Splitter,
+    Resolver,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct DiscoveryRoute {
+    pub Definition: Option<ServiceRoute>,
+    pub NextNode: String,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct DiscoverySplit {
+    pub Definition: Option<ServiceSplit>,
+    pub Weight: f32,
+    pub NextNode: String,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct DiscoveryFailover {
+    /// IDs of the targets to fail over to, in order
+    pub Targets: Vec<String>,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct DiscoveryResolver {
+    /// Whether the resolver was synthesized because no `service-resolver` entry exists
+    pub Default: bool,
+    /// Duration, e.g. `5s`
+    pub ConnectTimeout: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub RequestTimeout: String,
+    /// ID of the target
+    pub Target: String,
+    pub Failover: Option<DiscoveryFailover>,
+}
+
+/// A step of the chain. Routers and splitters point to other nodes, resolvers to targets.
+#[derive(Clone, Serialize, Deserialize, Debug)]
+pub struct DiscoveryGraphNode {
+    pub Type: DiscoveryGraphNodeType,
+    pub Name: String,
+    #[serde(default)]
+    pub Routes: Vec<DiscoveryRoute>,
+    #[serde(default)]
+    pub Splits: Vec<DiscoverySplit>,
+    #[serde(default)]
+    pub Resolver: Option<DiscoveryResolver>,
+    #[serde(default)]
+    pub LoadBalancer: Option<LoadBalancer>,
+}
+
+/// A set of instances traffic can be sent to.
+#[derive(Clone, Default, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct DiscoveryTarget {
+    pub ID: String,
+    pub Service: String,
+    pub ServiceSubset: String,
+    pub Namespace: String,
+    pub Partition: String,
+    pub Datacenter: String,
+    pub Peer: String,
+    pub MeshGateway: MeshGatewayConfig,
+    pub Subset: ServiceResolverSubset,
+    /// Duration, e.g. `5s`
+    pub ConnectTimeout: String,
+    pub External: bool,
+    pub SNI: String,
+    pub Name: String,
+}
+
+/// The routers, splitters and resolvers applying to a service, compiled into a graph
+/// starting at `StartNode`.
+#[derive(Clone, Default, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct CompiledDiscoveryChain {
+    pub ServiceName: String,
+    pub Namespace: String,
+    pub Partition: String,
+    pub Datacenter: String,
+    /// Hash of the overrides the chain was compiled with
+    pub CustomizationHash: String,
+    /// Whether the chain only consists of default nodes, no config entry applies
+    pub Default: bool,
+    pub Protocol: String,
+    pub ServiceMeta: HashMap<String, String>,
+    pub StartNode: String,
+    pub Nodes: HashMap<String, DiscoveryGraphNode>,
+    pub Targets: HashMap<String, DiscoveryTarget>,
+}
+
+impl CompiledDiscoveryChain {
+    pub fn start_node(&self) -> Option<&DiscoveryGraphNode> {
+        self.Nodes.get(&self.StartNode)
+    }
+
+    pub fn node(&self, name: &str) -> Option<&DiscoveryGraphNode> {
+        self.Nodes.get(name)
+    }
+
+    pub fn target(&self, id: &str) -> Option<&DiscoveryTarget> {
+        self.Targets.get(id)
+    }
+}
+
+#[derive(Default, Deserialize, Debug)]
+#[serde(default)]
+struct DiscoveryChainResponse {
+    Chain: CompiledDiscoveryChain,
+}
+
+#[async_trait]
+pub trait DiscoveryChain {
+    async fn discovery_chain(
+        &self,
+        service: &str,
+        options: Option<&DiscoveryChainOptions>,
+        q: Option<&QueryOptions>,
+    ) -> Result<(CompiledDiscoveryChain, QueryMeta)>;
+}
+
+#[async_trait]
+impl DiscoveryChain for Client {
+    /// https://developer.hashicorp.com/consul/api-docs/discovery-chain#read-compiled-discovery-chain
+    ///
+    /// The chain is read with a POST when overrides are set, both support blocking queries.
+    async fn discovery_chain(
+        &self,
+        service: &str,
+        options: Option<&DiscoveryChainOptions>,
+        q: Option<&QueryOptions>,
+    ) -> Result<(CompiledDiscoveryChain, QueryMeta)> {
+        let path = format!("/v1/discovery-chain/{}", service);
+        let mut params = HashMap::new();
+        if let Some(dc) = options.and_then(|o| o.EvaluateInDatacenter.as_ref()) {
+            params.insert(String::from("compile-dc"), dc.to_owned());
+        }
+        let (response, meta): (DiscoveryChainResponse, _) = match options {
+            Some(options) if options.has_overrides() => {
+                post_query(&path, options, &self.config, params, q).await?
+            }
+            _ => get(&path, &self.config, params, q).await?,
+        };
+        Ok((response.Chain, meta))
+    }
+}
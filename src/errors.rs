@@ -13,6 +13,35 @@ pub enum Error {
     ReqwestError(#[from] reqwest::Error),
     #[error("Session flag is required to acquire lock")]
     RequireSessionFlag,
+    #[error("TTL must be at least 1s, got {0:?}")]
+    InvalidTtl(std::time::Duration),
+    #[error("Relay factor must be between 0 and 5, got {0}")]
+    InvalidRelayFactor(u8),
+    #[error("Gossip keyring not updated on every node: {0}")]
+    KeyringIncomplete(String),
+    #[error(transparent)]
+    IoError(#[from] std::io::Error),
+    #[error(transparent)]
+    JsonError(#[from] serde_json::Error),
+    #[error("Snapshot archive has no meta.json")]
+    SnapshotMissingMeta,
+    #[error("CA rotation incomplete: {0}")]
+    CARotationIncomplete(String),
+    #[error("Invalid certificate: {0}")]
+    InvalidCertificate(String),
+    #[error("Invalid SPIFFE ID: {0}")]
+    InvalidSpiffeId(String),
+    #[error("Connection not authorized: {0}")]
+    Unauthorized(String),
+    #[error("No healthy instance of {0}")]
+    NoInstance(String),
+    #[cfg(feature = "connect")]
+    #[error(transparent)]
+    TlsError(#[from] rustls::Error),
+    #[error("Peering {0} did not reach the expected state in time")]
+    PeeringTimeout(String),
+    #[error("Consul is not ready: {0}")]
+    NotReady(String),
     #[error("Error parsing X-Consul-Index")]
     ParseConsulIndexError(#[from] ParseIntError),
 }
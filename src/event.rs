@@ -0,0 +1,158 @@
+use async_trait::async_trait;
+use std::collections::{HashMap, VecDeque};
+
+use crate::errors::Result;
+use crate::request::{get, put_body};
+use crate::{Client, QueryMeta, QueryOptions, WriteMeta, WriteOptions};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Default, Eq, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct UserEvent {
+    pub ID: String,
+    pub Name: String,
+    /// Base64 encoded payload
+    pub Payload: Option<String>,
+    pub NodeFilter: String,
+    pub ServiceFilter: String,
+    pub TagFilter: String,
+    pub Version: u32,
+    pub LTime: u64,
+}
+
+/// Restricts the nodes that handle an event. Filters are regular expressions,
+/// `tag` is only used along with `service`.
+#[derive(Clone, Default, Debug)]
+pub struct EventFilter {
+    pub node: Option<String>,
+    pub service: Option<String>,
+    pub tag: Option<String>,
+}
+
+/// Converts an event ID to the index used by `/v1/event/list` for blocking queries,
+/// by XORing the two halves of the UUID. Returns `None` if `uuid` is malformed.
+pub fn idx_from_uuid(uuid: &str) -> Option<u64> {
+    if uuid.len() != 36 || !uuid.is_ascii() {
+        return None;
+    }
+    let lower = format!("{}{}{}", &uuid[0..8], &uuid[9..13], &uuid[14..18]);
+    let upper = format!("{}{}", &uuid[19..23], &uuid[24..36]);
+    let low = u64::from_str_radix(&lower, 16).ok()?;
+    let high = u64::from_str_radix(&upper, 16).ok()?;
+    Some(low ^ high)
+}
+
+#[async_trait]
+pub trait Event {
+    async fn event_fire(
+        &self,
+        name: &str,
+        payload: Option<&[u8]>,
+        filter: &EventFilter,
+        q: Option<&WriteOptions>,
+    ) -> Result<(UserEvent, WriteMeta)>;
+    async fn event_list(
+        &self,
+        name: Option<&str>,
+        q: Option<&QueryOptions>,
+    ) -> Result<(Vec<UserEvent>, QueryMeta)>;
+}
+
+#[async_trait]
+impl Event for Client {
+    /// https://developer.hashicorp.com/consul/api-docs/event#fire-event
+    async fn event_fire(
+        &self,
+        name: &str,
+        payload: Option<&[u8]>,
+        filter: &EventFilter,
+        q: Option<&WriteOptions>,
+    ) -> Result<(UserEvent, WriteMeta)> {
+        let mut params = HashMap::new();
+        if let Some(node) = &filter.node {
+            params.insert(String::from("node"), node.to_owned());
+        }
+        if let Some(service) = &filter.service {
+            params.insert(String::from("service"), service.to_owned());
+        }
+        if let Some(tag) = &filter.tag {
+            params.insert(String::from("tag"), tag.to_owned());
+        }
+        let path = format!("/v1/event/fire/{}", name);
+        let body = payload.map(|p| p.to_vec()).unwrap_or_default();
+        put_body(&path, body, &self.config, params, q).await
+    }
+
+    /// https://developer.hashicorp.com/consul/api-docs/event#list-events
+    ///
+    /// Only the most recent events known by the agent are returned, oldest first.
+    async fn event_list(
+        &self,
+        name: Option<&str>,
+        q: Option<&QueryOptions>,
+    ) -> Result<(Vec<UserEvent>, QueryMeta)> {
+        let mut params = HashMap::new();
+        if let Some(name) = name {
+            params.insert(String::from("name"), name.to_owned());
+        }
+        get("/v1/event/list", &self.config, params, q).await
+    }
+}
+
+/// Long-polls `/v1/event/list` and yields each new event once.
+///
+/// Events already known by the agent when the watch starts are skipped.
+pub struct EventWatch {
+    client: Client,
+    name: Option<String>,
+    options: QueryOptions,
+    index: Option<u64>,
+    pending: VecDeque<UserEvent>,
+}
+
+impl EventWatch {
+    pub fn new(client: Client, name: Option<&str>) -> Self {
+        EventWatch {
+            client,
+            name: name.map(String::from),
+            options: QueryOptions::default(),
+            index: None,
+            pending: VecDeque::new(),
+        }
+    }
+
+    /// Options of the blocking queries, `wait_index` is managed by the watch.
+    pub fn query_options(mut self, options: QueryOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Waits for the next event. On error, the watch can be resumed by calling `next` again.
+    pub async fn next(&mut self) -> Result<UserEvent> {
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                return Ok(event);
+            }
+            self.options.wait_index = self.index;
+            let (events, meta) = self
+                .client
+                .event_list(self.name.as_deref(), Some(&self.options))
+                .await?;
+            let new_index = meta.last_index.unwrap_or(0);
+            let previous = match self.index.replace(new_index) {
+                Some(previous) => previous,
+                None => continue,
+            };
+            if new_index == previous {
+                continue;
+            }
+            // Events up to the last one seen were already delivered. If it is no
+            // longer in the agent buffer, all the events are new.
+            let start = events
+                .iter()
+                .position(|e| idx_from_uuid(&e.ID) == Some(previous))
+                .map_or(0, |i| i + 1);
+            self.pending.extend(events.into_iter().skip(start));
+        }
+    }
+}
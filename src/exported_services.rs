@@ -0,0 +1,115 @@
+use async_trait::async_trait;
+use std::collections::HashMap;
+
+use crate::errors::Result;
+use crate::request::get;
+use crate::{Client, QueryMeta, QueryOptions};
+use serde::{Deserialize, Serialize};
+
+/// Who a service is exported to, once the `exported-services` config entries and the
+/// sameness groups are resolved.
+#[derive(Clone, Default, Eq, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct ResolvedConsumers {
+    pub Peers: Vec<String>,
+    pub Partitions: Vec<String>,
+    pub SamenessGroups: Vec<String>,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct ResolvedExportedService {
+    pub Service: String,
+    pub Namespace: String,
+    pub Partition: String,
+    pub Consumers: ResolvedConsumers,
+}
+
+impl ResolvedExportedService {
+    pub fn is_exported_to_peer(&self, peer: &str) -> bool {
+        self.Consumers.Peers.iter().any(|p| p == peer)
+    }
+}
+
+/// A service imported from a cluster peer or from another partition.
+#[derive(Clone, Default, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct ImportedService {
+    pub Service: String,
+    pub Namespace: String,
+    pub Partition: String,
+    pub SourcePeer: String,
+    pub SourcePartition: String,
+}
+
+#[async_trait]
+pub trait ExportedServices {
+    async fn exported_services(
+        &self,
+        q: Option<&QueryOptions>,
+    ) -> Result<(Vec<ResolvedExportedService>, QueryMeta)>;
+    async fn imported_services(
+        &self,
+        q: Option<&QueryOptions>,
+    ) -> Result<(Vec<ImportedService>, QueryMeta)>;
+    async fn is_reachable_from_peer(
+        &self,
+        service: &str,
+        peer: &str,
+        q: Option<&QueryOptions>,
+    ) -> Result<bool>;
+}
+
+#[async_trait]
+impl ExportedServices for Client {
+    /// https://developer.hashicorp.com/consul/api-docs/exported-services#list-exported-services
+    async fn exported_services(
+        &self,
+        q: Option<&QueryOptions>,
+    ) -> Result<(Vec<ResolvedExportedService>, QueryMeta)> {
+        get("/v1/exported-services", &self.config, HashMap::new(), q).await
+    }
+
+    /// https://developer.hashicorp.com/consul/api-docs/imported-services#list-imported-services
+    async fn imported_services(
+        &self,
+        q: Option<&QueryOptions>,
+    ) -> Result<(Vec<ImportedService>, QueryMeta)> {
+        get("/v1/imported-services", &self.config, HashMap::new(), q).await
+    }
+
+    /// Whether `service`, in the namespace and partition the request is scoped to, is
+    /// exported to `peer`. Only peers listed directly as consumers count, exports to a
+    /// sameness group that `peer` is a member of are not considered.
+    async fn is_reachable_from_peer(
+        &self,
+        service: &str,
+        peer: &str,
+        q: Option<&QueryOptions>,
+    ) -> Result<bool> {
+        let scope = |option: Option<&String>, config: &Option<String>| {
+            option
+                .or(config.as_ref())
+                .map_or("default", String::as_str)
+                .to_owned()
+        };
+        let namespace = scope(q.and_then(|q| q.namespace.as_ref()), &self.config.namespace);
+        let partition = scope(q.and_then(|q| q.partition.as_ref()), &self.config.partition);
+        let (exported, _) = self.exported_services(q).await?;
+        Ok(exported.iter().any(|s| {
+            s.Service == service
+                && or_default(&s.Namespace) == namespace
+                && or_default(&s.Partition) == partition
+                && s.is_exported_to_peer(peer)
+        }))
+    }
+}
+
+/// Consul leaves out the default namespace and partition.
+fn or_default(scope: &str) -> &str {
+    if scope.is_empty() {
+        "default"
+    } else {
+        scope
+    }
+}
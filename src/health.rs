@@ -7,7 +7,7 @@ use crate::request::get;
 use crate::{Client, QueryMeta, QueryOptions};
 use serde::{Deserialize, Serialize};
 
-#[derive(Eq, Default, PartialEq, Serialize, Deserialize, Debug)]
+#[derive(Clone, Eq, Default, PartialEq, Serialize, Deserialize, Debug)]
 #[serde(default)]
 pub struct HealthCheck {
     pub Node: String,
@@ -21,7 +21,7 @@ pub struct HealthCheck {
     pub ServiceTags: Option<Vec<String>>,
 }
 
-#[derive(Eq, Default, PartialEq, Serialize, Deserialize, Debug)]
+#[derive(Clone, Eq, Default, PartialEq, Serialize, Deserialize, Debug)]
 #[serde(default)]
 pub struct Node {
     pub ID: String,
@@ -34,7 +34,7 @@ pub struct Node {
     pub ModifyIndex: u64,
 }
 
-#[derive(Default, Serialize, Deserialize, Debug)]
+#[derive(Clone, Default, Serialize, Deserialize, Debug)]
 #[serde(default)]
 pub struct ServiceEntry {
     pub Node: Node,
@@ -51,6 +51,13 @@ pub trait Health {
         passing_only: bool,
         options: Option<&QueryOptions>,
     ) -> Result<(Vec<ServiceEntry>, QueryMeta)>;
+    async fn connect(
+        &self,
+        service: &str,
+        tag: Option<&str>,
+        passing_only: bool,
+        options: Option<&QueryOptions>,
+    ) -> Result<(Vec<ServiceEntry>, QueryMeta)>;
 }
 
 #[async_trait]
@@ -72,4 +79,26 @@ impl Health for Client {
         }
         get(&path, &self.config, params, options).await
     }
+
+    /// https://developer.hashicorp.com/consul/api-docs/health#list-service-instances-for-connect-enabled-service
+    ///
+    /// Returns the Connect-capable instances of the service, that is its proxies and
+    /// its Connect-native instances.
+    async fn connect(
+        &self,
+        service: &str,
+        tag: Option<&str>,
+        passing_only: bool,
+        options: Option<&QueryOptions>,
+    ) -> Result<(Vec<ServiceEntry>, QueryMeta)> {
+        let mut params = HashMap::new();
+        let path = format!("/v1/health/connect/{}", service);
+        if passing_only {
+            params.insert(String::from("passing"), String::from("true"));
+        }
+        if let Some(tag) = tag {
+            params.insert(String::from("tag"), tag.to_owned());
+        }
+        get(&path, &self.config, params, options).await
+    }
 }
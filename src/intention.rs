@@ -0,0 +1,192 @@
+use async_trait::async_trait;
+use std::collections::HashMap;
+
+use crate::config_entry::{IntentionAction, IntentionPermission};
+use crate::errors::Result;
+use crate::request::{delete, get, put};
+use crate::{Client, QueryMeta, QueryOptions, WriteMeta, WriteOptions};
+use serde::{Deserialize, Serialize};
+
+/// An intention between a source and a destination service. An L4 intention has an
+/// `Action`, an L7 intention has `Permissions` instead.
+#[derive(Clone, Default, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct Intention {
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub ID: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Description: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub SourcePeer: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub SourceNS: String,
+    pub SourceName: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub SourcePartition: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub SourceSamenessGroup: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub DestinationNS: String,
+    pub DestinationName: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub DestinationPartition: String,
+    /// Always `consul`
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub SourceType: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Action: Option<IntentionAction>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub Permissions: Vec<IntentionPermission>,
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub Meta: HashMap<String, String>,
+    pub Precedence: i32,
+    pub CreateIndex: u64,
+    pub ModifyIndex: u64,
+}
+
+#[derive(Clone, Copy, Eq, PartialEq, Debug)]
+pub enum IntentionMatchType {
+    Source,
+    Destination,
+}
+
+impl IntentionMatchType {
+    fn as_str(self) -> &'static str {
+        match self {
+            IntentionMatchType::Source => "source",
+            IntentionMatchType::Destination => "destination",
+        }
+    }
+}
+
+#[derive(Default, Deserialize, Debug)]
+#[serde(default)]
+struct IntentionCheckResponse {
+    Allowed: bool,
+}
+
+#[async_trait]
+pub trait Intentions {
+    async fn intention_list(&self, q: Option<&QueryOptions>)
+        -> Result<(Vec<Intention>, QueryMeta)>;
+    async fn intention_get_exact(
+        &self,
+        source: &str,
+        destination: &str,
+        q: Option<&QueryOptions>,
+    ) -> Result<(Intention, QueryMeta)>;
+    async fn intention_upsert(
+        &self,
+        intention: &Intention,
+        q: Option<&WriteOptions>,
+    ) -> Result<((), WriteMeta)>;
+    async fn intention_delete_exact(
+        &self,
+        source: &str,
+        destination: &str,
+        q: Option<&WriteOptions>,
+    ) -> Result<((), WriteMeta)>;
+    async fn intention_match(
+        &self,
+        by: IntentionMatchType,
+        name: &str,
+        q: Option<&QueryOptions>,
+    ) -> Result<(Vec<Intention>, QueryMeta)>;
+    async fn intention_check(
+        &self,
+        source: &str,
+        destination: &str,
+        q: Option<&QueryOptions>,
+    ) -> Result<(bool, QueryMeta)>;
+}
+
+fn exact_params(source: &str, destination: &str) -> HashMap<String, String> {
+    let mut params = HashMap::new();
+    params.insert(String::from("source"), source.to_owned());
+    params.insert(String::from("destination"), destination.to_owned());
+    params
+}
+
+#[async_trait]
+impl Intentions for Client {
+    /// https://developer.hashicorp.com/consul/api-docs/connect/intentions#list-intentions
+    async fn intention_list(
+        &self,
+        q: Option<&QueryOptions>,
+    ) -> Result<(Vec<Intention>, QueryMeta)> {
+        get("/v1/connect/intentions", &self.config, HashMap::new(), q).await
+    }
+
+    /// https://developer.hashicorp.com/consul/api-docs/connect/intentions#read-specific-intention-by-name
+    async fn intention_get_exact(
+        &self,
+        source: &str,
+        destination: &str,
+        q: Option<&QueryOptions>,
+    ) -> Result<(Intention, QueryMeta)> {
+        let params = exact_params(source, destination);
+        get("/v1/connect/intentions/exact", &self.config, params, q).await
+    }
+
+    /// https://developer.hashicorp.com/consul/api-docs/connect/intentions#upsert-intention-by-name
+    ///
+    /// The source and destination are taken from `SourceName` and `DestinationName`.
+    async fn intention_upsert(
+        &self,
+        intention: &Intention,
+        q: Option<&WriteOptions>,
+    ) -> Result<((), WriteMeta)> {
+        let params = exact_params(&intention.SourceName, &intention.DestinationName);
+        put(
+            "/v1/connect/intentions/exact",
+            Some(intention),
+            &self.config,
+            params,
+            q,
+        )
+        .await
+    }
+
+    /// https://developer.hashicorp.com/consul/api-docs/connect/intentions#delete-intention-by-name
+    async fn intention_delete_exact(
+        &self,
+        source: &str,
+        destination: &str,
+        q: Option<&WriteOptions>,
+    ) -> Result<((), WriteMeta)> {
+        let params = exact_params(source, destination);
+        delete("/v1/connect/intentions/exact", &self.config, params, q).await
+    }
+
+    /// https://developer.hashicorp.com/consul/api-docs/connect/intentions#list-matching-intentions
+    ///
+    /// The intentions are ordered by precedence, the first one applies.
+    async fn intention_match(
+        &self,
+        by: IntentionMatchType,
+        name: &str,
+        q: Option<&QueryOptions>,
+    ) -> Result<(Vec<Intention>, QueryMeta)> {
+        let mut params = HashMap::new();
+        params.insert(String::from("by"), by.as_str().to_owned());
+        params.insert(String::from("name"), name.to_owned());
+        let (mut matches, meta): (HashMap<String, Vec<Intention>>, _) =
+            get("/v1/connect/intentions/match", &self.config, params, q).await?;
+        Ok((matches.remove(name).unwrap_or_default(), meta))
+    }
+
+    /// https://developer.hashicorp.com/consul/api-docs/connect/intentions#check-intention-result
+    ///
+    /// Returns whether a connection from `source` to `destination` would be allowed.
+    async fn intention_check(
+        &self,
+        source: &str,
+        destination: &str,
+        q: Option<&QueryOptions>,
+    ) -> Result<(bool, QueryMeta)> {
+        let params = exact_params(source, destination);
+        let (response, meta): (IntentionCheckResponse, _) =
+            get("/v1/connect/intentions/check", &self.config, params, q).await?;
+        Ok((response.Allowed, meta))
+    }
+}
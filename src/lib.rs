@@ -3,22 +3,43 @@
 
 pub mod agent;
 pub mod catalog;
+pub mod config_entry;
+#[cfg(feature = "connect")]
+pub mod connect;
 pub mod connect_ca;
+pub mod coordinate;
+pub mod discovery;
+pub mod discovery_chain;
 pub mod errors;
+pub mod event;
+pub mod exported_services;
 pub mod health;
+pub mod intention;
 pub mod kv;
+pub mod namespace;
+pub mod operator;
+pub mod partition;
+pub mod peering;
+pub mod prepared_query;
+pub mod registration;
+pub mod resolver;
 pub mod session;
+pub mod snapshot;
+pub mod spiffe;
+pub mod status;
 
 mod request;
 
 use std::env;
 
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use reqwest::Client as HttpClient;
 use reqwest::ClientBuilder;
 
-use errors::Result;
+use agent::{Agent, MemberStatus};
+use errors::{Error, Result};
+use status::Status;
 
 #[derive(Clone, Debug)]
 pub struct Client {
@@ -29,6 +50,50 @@ impl Client {
     pub fn new(config: Config) -> Self {
         Client { config }
     }
+
+    /// A client making its requests with `token` instead of `Config::token`.
+    /// The HTTP connection pool is shared with `self`.
+    pub fn with_token(&self, token: &str) -> Self {
+        let mut config = self.config.clone();
+        config.token = Some(token.to_owned());
+        Client { config }
+    }
+
+    /// Waits until the cluster has a leader and the local agent is a live member of it,
+    /// polling with an exponential backoff. Fails with `Error::NotReady` after `timeout`.
+    pub async fn wait_until_ready(&self, timeout: Duration) -> Result<()> {
+        let deadline = Instant::now() + timeout;
+        let mut delay = Duration::from_millis(100);
+        loop {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            let reason = match tokio::time::timeout(remaining, self.check_ready()).await {
+                Ok(Ok(None)) => return Ok(()),
+                Ok(Ok(Some(reason))) => reason,
+                Ok(Err(e)) => e.to_string(),
+                Err(_) => String::from("no answer from the agent"),
+            };
+            let now = Instant::now();
+            if now >= deadline {
+                return Err(Error::NotReady(reason));
+            }
+            tokio::time::sleep(delay.min(deadline - now)).await;
+            delay = (delay * 2).min(Duration::from_secs(2));
+        }
+    }
+
+    async fn check_ready(&self) -> Result<Option<String>> {
+        let (leader, _) = self.leader(None).await?;
+        if leader.is_empty() {
+            return Ok(Some(String::from("no cluster leader")));
+        }
+        let node = self.agent_self().await?.Config.NodeName;
+        let members = self.members(false, None).await?;
+        match members.iter().find(|m| m.Name == node) {
+            Some(m) if m.Status == MemberStatus::Alive => Ok(None),
+            Some(m) => Ok(Some(format!("local agent {} is {:?}", node, m.Status))),
+            None => Ok(Some(format!("local agent {} is not a member", node))),
+        }
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -38,6 +103,10 @@ pub struct Config {
     pub http_client: HttpClient,
     pub token: Option<String>,
     pub wait_time: Option<Duration>,
+    /// Namespace of the requests, Consul Enterprise only
+    pub namespace: Option<String>,
+    /// Admin partition of the requests, Consul Enterprise only
+    pub partition: Option<String>,
 }
 
 impl Config {
@@ -48,6 +117,8 @@ impl Config {
             http_client: client,
             token: None,
             wait_time: None,
+            namespace: None,
+            partition: None,
         })?;
         Ok(conf)
     }
@@ -64,12 +135,17 @@ impl Config {
             Err(_e) => String::from("http://127.0.0.1:8500"),
         };
         let consul_token = env::var("CONSUL_HTTP_TOKEN").ok();
+        // Empty variables are ignored, like the Consul CLI does
+        let namespace = env::var("CONSUL_NAMESPACE").ok().filter(|v| !v.is_empty());
+        let partition = env::var("CONSUL_PARTITION").ok().filter(|v| !v.is_empty());
         let conf = ClientBuilder::new().build().map(|client| Config {
             address: consul_addr,
             datacenter: None,
             http_client: client,
             token: consul_token,
             wait_time: None,
+            namespace,
+            partition,
         })?;
         Ok(conf)
     }
@@ -85,6 +161,8 @@ impl Config {
             http_client: client,
             token,
             wait_time: None,
+            namespace: None,
+            partition: None,
         })?;
         Ok(conf)
     }
@@ -96,6 +174,8 @@ impl Config {
             http_client: client,
             token,
             wait_time: None,
+            namespace: None,
+            partition: None,
         })?;
         Ok(conf)
     }
@@ -105,18 +185,52 @@ impl Config {
 pub struct QueryOptions {
     pub datacenter: Option<String>,
     pub wait_index: Option<u64>,
+    /// Blocks on `X-Consul-ContentHash` instead of an index, for the endpoints that support it.
+    pub wait_hash: Option<String>,
     pub wait_time: Option<Duration>,
+    /// Reads the services imported from a cluster peer, for the health and catalog endpoints.
+    pub peer: Option<String>,
+    /// Overrides `Config::namespace`
+    pub namespace: Option<String>,
+    /// Overrides `Config::partition`
+    pub partition: Option<String>,
+    /// Overrides `Config::token`
+    pub token: Option<String>,
+    /// Selects `stale` or `consistent` reads, the server's default mode otherwise
+    pub consistency: Option<ConsistencyMode>,
+    /// Number of random members relaying the responses, from 0 to 5. Only sent by
+    /// `Operator::keyring_list`.
+    pub relay_factor: Option<u8>,
+}
+
+/// https://developer.hashicorp.com/consul/api-docs/features/consistency
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ConsistencyMode {
+    /// Reads go through the leader, which first checks that it still is the leader.
+    Consistent,
+    /// Any server can answer, possibly with stale data.
+    Stale,
 }
 
 #[derive(Clone, Debug)]
 pub struct QueryMeta {
     pub last_index: Option<u64>,
+    pub last_content_hash: Option<String>,
     pub request_time: Duration,
 }
 
 #[derive(Clone, Debug, Default)]
 pub struct WriteOptions {
     pub datacenter: Option<String>,
+    /// Overrides `Config::namespace`
+    pub namespace: Option<String>,
+    /// Overrides `Config::partition`
+    pub partition: Option<String>,
+    /// Overrides `Config::token`
+    pub token: Option<String>,
+    /// Number of random members relaying the responses, from 0 to 5. Only sent by the
+    /// keyring operations.
+    pub relay_factor: Option<u8>,
 }
 
 #[derive(Clone, Debug)]
@@ -0,0 +1,133 @@
+use async_trait::async_trait;
+use std::collections::HashMap;
+
+use crate::errors::Result;
+use crate::request::{delete, get, get_optional, put};
+use crate::{Client, QueryMeta, QueryOptions, WriteMeta, WriteOptions};
+use serde::{Deserialize, Serialize};
+
+/// Reference to an ACL policy or role, by ID or by name.
+#[derive(Clone, Default, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct ACLLink {
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub ID: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Name: String,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct NamespaceACLConfig {
+    /// Policies applied to the tokens of the namespace
+    pub PolicyDefaults: Vec<ACLLink>,
+    /// Roles applied to the tokens of the namespace
+    pub RoleDefaults: Vec<ACLLink>,
+}
+
+/// A Consul Enterprise namespace.
+#[derive(Clone, Default, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct Namespace {
+    pub Name: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Description: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ACLs: Option<NamespaceACLConfig>,
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub Meta: HashMap<String, String>,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Partition: String,
+    /// RFC 3339 timestamp, set once the namespace is marked for deletion
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub DeletedAt: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub CreateIndex: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ModifyIndex: Option<u64>,
+}
+
+#[async_trait]
+pub trait Namespaces {
+    async fn namespace_create(
+        &self,
+        namespace: &Namespace,
+        q: Option<&WriteOptions>,
+    ) -> Result<(Namespace, WriteMeta)>;
+    async fn namespace_read(
+        &self,
+        name: &str,
+        q: Option<&QueryOptions>,
+    ) -> Result<(Option<Namespace>, QueryMeta)>;
+    async fn namespace_update(
+        &self,
+        namespace: &Namespace,
+        q: Option<&WriteOptions>,
+    ) -> Result<(Namespace, WriteMeta)>;
+    async fn namespace_delete(
+        &self,
+        name: &str,
+        q: Option<&WriteOptions>,
+    ) -> Result<((), WriteMeta)>;
+    async fn namespace_list(&self, q: Option<&QueryOptions>)
+        -> Result<(Vec<Namespace>, QueryMeta)>;
+}
+
+#[async_trait]
+impl Namespaces for Client {
+    /// https://developer.hashicorp.com/consul/api-docs/namespaces#create-a-namespace
+    async fn namespace_create(
+        &self,
+        namespace: &Namespace,
+        q: Option<&WriteOptions>,
+    ) -> Result<(Namespace, WriteMeta)> {
+        put(
+            "/v1/namespace",
+            Some(namespace),
+            &self.config,
+            HashMap::new(),
+            q,
+        )
+        .await
+    }
+
+    /// https://developer.hashicorp.com/consul/api-docs/namespaces#read-a-namespace
+    async fn namespace_read(
+        &self,
+        name: &str,
+        q: Option<&QueryOptions>,
+    ) -> Result<(Option<Namespace>, QueryMeta)> {
+        let path = format!("/v1/namespace/{}", name);
+        get_optional(&path, &self.config, HashMap::new(), q).await
+    }
+
+    /// https://developer.hashicorp.com/consul/api-docs/namespaces#update-a-namespace
+    async fn namespace_update(
+        &self,
+        namespace: &Namespace,
+        q: Option<&WriteOptions>,
+    ) -> Result<(Namespace, WriteMeta)> {
+        let path = format!("/v1/namespace/{}", namespace.Name);
+        put(&path, Some(namespace), &self.config, HashMap::new(), q).await
+    }
+
+    /// https://developer.hashicorp.com/consul/api-docs/namespaces#delete-a-namespace
+    ///
+    /// The namespace is marked for deletion and removed once its resources are.
+    async fn namespace_delete(
+        &self,
+        name: &str,
+        q: Option<&WriteOptions>,
+    ) -> Result<((), WriteMeta)> {
+        let path = format!("/v1/namespace/{}", name);
+        delete(&path, &self.config, HashMap::new(), q).await
+    }
+
+    /// https://developer.hashicorp.com/consul/api-docs/namespaces#list-all-namespaces
+    async fn namespace_list(
+        &self,
+        q: Option<&QueryOptions>,
+    ) -> Result<(Vec<Namespace>, QueryMeta)> {
+        get("/v1/namespaces", &self.config, HashMap::new(), q).await
+    }
+}
@@ -0,0 +1,445 @@
+use async_trait::async_trait;
+use std::collections::HashMap;
+
+use crate::errors::{Error, Result};
+use crate::request::{add_relay_factor, delete, delete_with_body, get, get_with_status, post, put};
+use crate::{Client, QueryMeta, QueryOptions, WriteMeta, WriteOptions};
+use reqwest::StatusCode;
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Default, Eq, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct RaftServer {
+    pub ID: String,
+    pub Node: String,
+    pub Address: String,
+    pub Leader: bool,
+    pub ProtocolVersion: String,
+    pub Voter: bool,
+}
+
+#[derive(Clone, Default, Eq, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct RaftConfiguration {
+    pub Servers: Vec<RaftServer>,
+    pub Index: u64,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, Debug)]
+#[serde(default)]
+struct TransferLeaderResponse {
+    Success: bool,
+}
+
+#[derive(Clone, Default, Eq, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct AutopilotConfiguration {
+    pub CleanupDeadServers: bool,
+    /// Duration, e.g. `200ms`
+    pub LastContactThreshold: String,
+    pub MaxTrailingLogs: u64,
+    pub MinQuorum: u32,
+    /// Duration, e.g. `10s`
+    pub ServerStabilizationTime: String,
+    pub RedundancyZoneTag: String,
+    pub DisableUpgradeMigration: bool,
+    pub UpgradeVersionTag: String,
+    pub CreateIndex: u64,
+    pub ModifyIndex: u64,
+}
+
+#[derive(Clone, Default, Eq, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct ServerHealth {
+    pub ID: String,
+    pub Name: String,
+    pub Address: String,
+    pub SerfStatus: String,
+    pub Version: String,
+    pub Leader: bool,
+    /// Duration since the last contact with the leader, e.g. `12.5ms`
+    pub LastContact: String,
+    pub LastTerm: u64,
+    pub LastIndex: u64,
+    pub Healthy: bool,
+    pub Voter: bool,
+    pub StableSince: String,
+}
+
+#[derive(Clone, Default, Eq, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct OperatorHealthReply {
+    pub Healthy: bool,
+    /// Number of servers that can fail without losing quorum
+    pub FailureTolerance: u32,
+    pub Servers: Vec<ServerHealth>,
+}
+
+impl OperatorHealthReply {
+    pub fn server(&self, id: &str) -> Option<&ServerHealth> {
+        self.Servers.iter().find(|s| s.ID == id)
+    }
+}
+
+#[derive(Clone, Default, Eq, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct AutopilotServer {
+    pub ID: String,
+    pub Name: String,
+    pub Address: String,
+    pub NodeStatus: String,
+    pub Version: String,
+    pub LastContact: String,
+    pub LastTerm: u64,
+    pub LastIndex: u64,
+    pub Healthy: bool,
+    pub StableSince: String,
+    pub RedundancyZone: String,
+    pub UpgradeVersion: String,
+    pub ReadReplica: bool,
+    /// One of `leader`, `voter`, `non-voter` or `staging`
+    pub Status: String,
+    pub Meta: HashMap<String, String>,
+    pub NodeType: String,
+}
+
+#[derive(Clone, Default, Eq, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct AutopilotZone {
+    pub Servers: Vec<String>,
+    pub Voters: Vec<String>,
+    pub FailureTolerance: u32,
+}
+
+#[derive(Clone, Default, Eq, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct AutopilotUpgrade {
+    pub Status: String,
+    pub TargetVersion: String,
+    pub TargetVersionVoters: Vec<String>,
+    pub TargetVersionNonVoters: Vec<String>,
+    pub TargetVersionReadReplicas: Vec<String>,
+    pub OtherVersionVoters: Vec<String>,
+    pub OtherVersionNonVoters: Vec<String>,
+    pub OtherVersionReadReplicas: Vec<String>,
+}
+
+#[derive(Clone, Default, Eq, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct AutopilotState {
+    pub Healthy: bool,
+    pub FailureTolerance: u32,
+    pub OptimisticFailureTolerance: u32,
+    pub Servers: HashMap<String, AutopilotServer>,
+    pub Leader: String,
+    pub Voters: Vec<String>,
+    pub ReadReplicas: Vec<String>,
+    pub RedundancyZones: HashMap<String, AutopilotZone>,
+    pub Upgrade: Option<AutopilotUpgrade>,
+}
+
+/// The gossip keyring of one pool of the cluster, as reported by its members.
+#[derive(Clone, Default, Eq, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct KeyringResponse {
+    pub WAN: bool,
+    pub Datacenter: String,
+    pub Segment: String,
+    pub Partition: String,
+    /// Number of nodes that have each key installed
+    pub Keys: HashMap<String, u32>,
+    /// Number of nodes that use each key to encrypt messages, since Consul 1.13
+    pub PrimaryKeys: HashMap<String, u32>,
+    pub NumNodes: u32,
+    /// Errors reported by nodes, by node name
+    pub Messages: HashMap<String, String>,
+}
+
+impl KeyringResponse {
+    /// Whether every node of the pool has `key` installed.
+    pub fn is_installed_everywhere(&self, key: &str) -> bool {
+        self.Keys.get(key) == Some(&self.NumNodes)
+    }
+
+    /// Whether every node of the pool uses `key` as its primary key. Always true on
+    /// Consul versions that don't report primary keys.
+    pub fn is_primary_everywhere(&self, key: &str) -> bool {
+        self.PrimaryKeys.is_empty() || self.PrimaryKeys.get(key) == Some(&self.NumNodes)
+    }
+
+    fn pool(&self) -> String {
+        let pool = if self.WAN { "WAN" } else { "LAN" };
+        format!("{} pool of {}", pool, self.Datacenter)
+    }
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, Debug)]
+struct KeyringRequest<'a> {
+    Key: &'a str,
+}
+
+#[async_trait]
+pub trait Operator {
+    async fn raft_get_configuration(
+        &self,
+        q: Option<&QueryOptions>,
+    ) -> Result<(RaftConfiguration, QueryMeta)>;
+    async fn raft_remove_peer_by_id(
+        &self,
+        id: &str,
+        q: Option<&WriteOptions>,
+    ) -> Result<((), WriteMeta)>;
+    async fn raft_remove_peer_by_address(
+        &self,
+        address: &str,
+        q: Option<&WriteOptions>,
+    ) -> Result<((), WriteMeta)>;
+    async fn raft_transfer_leader(
+        &self,
+        id: Option<&str>,
+        q: Option<&WriteOptions>,
+    ) -> Result<(bool, WriteMeta)>;
+    async fn autopilot_get_configuration(
+        &self,
+        q: Option<&QueryOptions>,
+    ) -> Result<(AutopilotConfiguration, QueryMeta)>;
+    async fn autopilot_set_configuration(
+        &self,
+        conf: &AutopilotConfiguration,
+        cas: bool,
+        q: Option<&WriteOptions>,
+    ) -> Result<(bool, WriteMeta)>;
+    async fn autopilot_server_health(
+        &self,
+        q: Option<&QueryOptions>,
+    ) -> Result<(OperatorHealthReply, QueryMeta)>;
+    async fn autopilot_state(
+        &self,
+        q: Option<&QueryOptions>,
+    ) -> Result<(AutopilotState, QueryMeta)>;
+    async fn keyring_list(
+        &self,
+        q: Option<&QueryOptions>,
+    ) -> Result<(Vec<KeyringResponse>, QueryMeta)>;
+    async fn keyring_install(&self, key: &str, q: Option<&WriteOptions>)
+        -> Result<((), WriteMeta)>;
+    async fn keyring_use(&self, key: &str, q: Option<&WriteOptions>) -> Result<((), WriteMeta)>;
+    async fn keyring_remove(&self, key: &str, q: Option<&WriteOptions>) -> Result<((), WriteMeta)>;
+}
+
+#[async_trait]
+impl Operator for Client {
+    /// https://developer.hashicorp.com/consul/api-docs/operator/raft#read-configuration
+    async fn raft_get_configuration(
+        &self,
+        q: Option<&QueryOptions>,
+    ) -> Result<(RaftConfiguration, QueryMeta)> {
+        get(
+            "/v1/operator/raft/configuration",
+            &self.config,
+            HashMap::new(),
+            q,
+        )
+        .await
+    }
+
+    /// https://developer.hashicorp.com/consul/api-docs/operator/raft#delete-raft-peer
+    async fn raft_remove_peer_by_id(
+        &self,
+        id: &str,
+        q: Option<&WriteOptions>,
+    ) -> Result<((), WriteMeta)> {
+        let mut params = HashMap::new();
+        params.insert(String::from("id"), id.to_owned());
+        delete("/v1/operator/raft/peer", &self.config, params, q).await
+    }
+
+    /// https://developer.hashicorp.com/consul/api-docs/operator/raft#delete-raft-peer
+    async fn raft_remove_peer_by_address(
+        &self,
+        address: &str,
+        q: Option<&WriteOptions>,
+    ) -> Result<((), WriteMeta)> {
+        let mut params = HashMap::new();
+        params.insert(String::from("address"), address.to_owned());
+        delete("/v1/operator/raft/peer", &self.config, params, q).await
+    }
+
+    /// https://developer.hashicorp.com/consul/api-docs/operator/raft#transfer-raft-leadership
+    ///
+    /// Without `id`, Consul picks the server to transfer the leadership to.
+    async fn raft_transfer_leader(
+        &self,
+        id: Option<&str>,
+        q: Option<&WriteOptions>,
+    ) -> Result<(bool, WriteMeta)> {
+        let mut params = HashMap::new();
+        if let Some(id) = id {
+            params.insert(String::from("id"), id.to_owned());
+        }
+        post(
+            "/v1/operator/raft/transfer-leader",
+            None as Option<&()>,
+            &self.config,
+            params,
+            q,
+        )
+        .await
+        .map(|(r, meta): (TransferLeaderResponse, WriteMeta)| (r.Success, meta))
+    }
+
+    /// https://developer.hashicorp.com/consul/api-docs/operator/autopilot#read-configuration
+    async fn autopilot_get_configuration(
+        &self,
+        q: Option<&QueryOptions>,
+    ) -> Result<(AutopilotConfiguration, QueryMeta)> {
+        get(
+            "/v1/operator/autopilot/configuration",
+            &self.config,
+            HashMap::new(),
+            q,
+        )
+        .await
+    }
+
+    /// https://developer.hashicorp.com/consul/api-docs/operator/autopilot#update-configuration
+    ///
+    /// With `cas`, the update only happens if `conf.ModifyIndex` is still current,
+    /// and `false` is returned otherwise.
+    async fn autopilot_set_configuration(
+        &self,
+        conf: &AutopilotConfiguration,
+        cas: bool,
+        q: Option<&WriteOptions>,
+    ) -> Result<(bool, WriteMeta)> {
+        let mut params = HashMap::new();
+        if cas {
+            params.insert(String::from("cas"), conf.ModifyIndex.to_string());
+        }
+        put(
+            "/v1/operator/autopilot/configuration",
+            Some(conf),
+            &self.config,
+            params,
+            q,
+        )
+        .await
+    }
+
+    /// https://developer.hashicorp.com/consul/api-docs/operator/autopilot#read-health
+    ///
+    /// An unhealthy cluster is returned as a value, check `Healthy`.
+    async fn autopilot_server_health(
+        &self,
+        q: Option<&QueryOptions>,
+    ) -> Result<(OperatorHealthReply, QueryMeta)> {
+        get_with_status(
+            "/v1/operator/autopilot/health",
+            &self.config,
+            HashMap::new(),
+            q,
+            &[StatusCode::TOO_MANY_REQUESTS],
+        )
+        .await
+    }
+
+    /// https://developer.hashicorp.com/consul/api-docs/operator/autopilot#read-the-autopilot-state
+    async fn autopilot_state(
+        &self,
+        q: Option<&QueryOptions>,
+    ) -> Result<(AutopilotState, QueryMeta)> {
+        get(
+            "/v1/operator/autopilot/state",
+            &self.config,
+            HashMap::new(),
+            q,
+        )
+        .await
+    }
+
+    /// https://developer.hashicorp.com/consul/api-docs/operator/keyring#list-gossip-encryption-keys
+    async fn keyring_list(
+        &self,
+        q: Option<&QueryOptions>,
+    ) -> Result<(Vec<KeyringResponse>, QueryMeta)> {
+        let mut params = HashMap::new();
+        add_relay_factor(&mut params, q.and_then(|q| q.relay_factor))?;
+        get("/v1/operator/keyring", &self.config, params, q).await
+    }
+
+    /// https://developer.hashicorp.com/consul/api-docs/operator/keyring#add-new-gossip-encryption-key
+    async fn keyring_install(
+        &self,
+        key: &str,
+        q: Option<&WriteOptions>,
+    ) -> Result<((), WriteMeta)> {
+        let mut params = HashMap::new();
+        add_relay_factor(&mut params, q.and_then(|q| q.relay_factor))?;
+        post(
+            "/v1/operator/keyring",
+            Some(&KeyringRequest { Key: key }),
+            &self.config,
+            params,
+            q,
+        )
+        .await
+    }
+
+    /// https://developer.hashicorp.com/consul/api-docs/operator/keyring#change-primary-gossip-encryption-key
+    async fn keyring_use(&self, key: &str, q: Option<&WriteOptions>) -> Result<((), WriteMeta)> {
+        let mut params = HashMap::new();
+        add_relay_factor(&mut params, q.and_then(|q| q.relay_factor))?;
+        put(
+            "/v1/operator/keyring",
+            Some(&KeyringRequest { Key: key }),
+            &self.config,
+            params,
+            q,
+        )
+        .await
+    }
+
+    /// https://developer.hashicorp.com/consul/api-docs/operator/keyring#delete-gossip-encryption-key
+    async fn keyring_remove(&self, key: &str, q: Option<&WriteOptions>) -> Result<((), WriteMeta)> {
+        let mut params = HashMap::new();
+        add_relay_factor(&mut params, q.and_then(|q| q.relay_factor))?;
+        delete_with_body(
+            "/v1/operator/keyring",
+            Some(&KeyringRequest { Key: key }),
+            &self.config,
+            params,
+            q,
+        )
+        .await
+    }
+}
+
+/// Replaces the gossip key `old_key` with `new_key`: installs the new key, makes it
+/// primary, then removes the old one. Each step is checked against every pool of the
+/// cluster before moving on, and the rotation stops at the first incomplete step.
+pub async fn rotate_key(client: &Client, old_key: &str, new_key: &str) -> Result<()> {
+    client.keyring_install(new_key, None).await?;
+    check_keyring(client, "install", |ring| {
+        ring.is_installed_everywhere(new_key)
+    })
+    .await?;
+    client.keyring_use(new_key, None).await?;
+    check_keyring(client, "use", |ring| ring.is_primary_everywhere(new_key)).await?;
+    client.keyring_remove(old_key, None).await?;
+    check_keyring(client, "remove", |ring| !ring.Keys.contains_key(old_key)).await
+}
+
+async fn check_keyring<F>(client: &Client, step: &str, done: F) -> Result<()>
+where
+    F: Fn(&KeyringResponse) -> bool,
+{
+    let (rings, _) = client.keyring_list(None).await?;
+    match rings.iter().find(|ring| !done(ring)) {
+        None => Ok(()),
+        Some(ring) => Err(Error::KeyringIncomplete(format!(
+            "{} step incomplete in the {}",
+            step,
+            ring.pool()
+        ))),
+    }
+}
@@ -0,0 +1,108 @@
+use async_trait::async_trait;
+use std::collections::HashMap;
+
+use crate::errors::Result;
+use crate::request::{delete, get, get_optional, put};
+use crate::{Client, QueryMeta, QueryOptions, WriteMeta, WriteOptions};
+use serde::{Deserialize, Serialize};
+
+/// A Consul Enterprise admin partition.
+#[derive(Clone, Default, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct Partition {
+    pub Name: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Description: String,
+    /// RFC 3339 timestamp, set once the partition is marked for deletion
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub DeletedAt: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub CreateIndex: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ModifyIndex: Option<u64>,
+}
+
+#[async_trait]
+pub trait Partitions {
+    async fn partition_create(
+        &self,
+        partition: &Partition,
+        q: Option<&WriteOptions>,
+    ) -> Result<(Partition, WriteMeta)>;
+    async fn partition_read(
+        &self,
+        name: &str,
+        q: Option<&QueryOptions>,
+    ) -> Result<(Option<Partition>, QueryMeta)>;
+    async fn partition_update(
+        &self,
+        partition: &Partition,
+        q: Option<&WriteOptions>,
+    ) -> Result<(Partition, WriteMeta)>;
+    async fn partition_delete(
+        &self,
+        name: &str,
+        q: Option<&WriteOptions>,
+    ) -> Result<((), WriteMeta)>;
+    async fn partition_list(&self, q: Option<&QueryOptions>)
+        -> Result<(Vec<Partition>, QueryMeta)>;
+}
+
+#[async_trait]
+impl Partitions for Client {
+    /// https://developer.hashicorp.com/consul/api-docs/admin-partitions#create-a-partition
+    async fn partition_create(
+        &self,
+        partition: &Partition,
+        q: Option<&WriteOptions>,
+    ) -> Result<(Partition, WriteMeta)> {
+        put(
+            "/v1/partition",
+            Some(partition),
+            &self.config,
+            HashMap::new(),
+            q,
+        )
+        .await
+    }
+
+    /// https://developer.hashicorp.com/consul/api-docs/admin-partitions#read-a-partition
+    async fn partition_read(
+        &self,
+        name: &str,
+        q: Option<&QueryOptions>,
+    ) -> Result<(Option<Partition>, QueryMeta)> {
+        let path = format!("/v1/partition/{}", name);
+        get_optional(&path, &self.config, HashMap::new(), q).await
+    }
+
+    /// https://developer.hashicorp.com/consul/api-docs/admin-partitions#update-a-partition
+    async fn partition_update(
+        &self,
+        partition: &Partition,
+        q: Option<&WriteOptions>,
+    ) -> Result<(Partition, WriteMeta)> {
+        let path = format!("/v1/partition/{}", partition.Name);
+        put(&path, Some(partition), &self.config, HashMap::new(), q).await
+    }
+
+    /// https://developer.hashicorp.com/consul/api-docs/admin-partitions#delete-a-partition
+    ///
+    /// The partition is marked for deletion and removed once its resources are.
+    async fn partition_delete(
+        &self,
+        name: &str,
+        q: Option<&WriteOptions>,
+    ) -> Result<((), WriteMeta)> {
+        let path = format!("/v1/partition/{}", name);
+        delete(&path, &self.config, HashMap::new(), q).await
+    }
+
+    /// https://developer.hashicorp.com/consul/api-docs/admin-partitions#list-all-partitions
+    async fn partition_list(
+        &self,
+        q: Option<&QueryOptions>,
+    ) -> Result<(Vec<Partition>, QueryMeta)> {
+        get("/v1/partitions", &self.config, HashMap::new(), q).await
+    }
+}
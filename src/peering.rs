@@ -0,0 +1,266 @@
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::time::Duration;
+
+use crate::errors::{Error, Result};
+use crate::request::{delete, get, get_optional, post};
+use crate::{Client, QueryMeta, QueryOptions, WriteMeta, WriteOptions};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Eq, PartialEq, Serialize, Deserialize, Debug, Default)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum PeeringState {
+    /// A token was generated, the peer didn't use it yet
+    Pending,
+    Establishing,
+    Active,
+    /// The peers can't reach each other
+    Failing,
+    /// Marked for deletion, resources are being cleaned up
+    Deleting,
+    /// The peer deleted the peering
+    Terminated,
+    #[default]
+    #[serde(other)]
+    Undefined,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct PeeringStreamStatus {
+    pub ImportedServices: Vec<String>,
+    pub ExportedServices: Vec<String>,
+    /// RFC 3339 timestamp
+    pub LastHeartbeat: Option<String>,
+    pub LastReceive: Option<String>,
+    pub LastSend: Option<String>,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct PeeringRemoteInfo {
+    pub Partition: String,
+    pub Datacenter: String,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct Peering {
+    pub ID: String,
+    pub Name: String,
+    pub Partition: String,
+    pub Meta: HashMap<String, String>,
+    pub PeeringState: PeeringState,
+    pub PeerID: String,
+    pub PeerCAPems: Vec<String>,
+    pub PeerServerName: String,
+    pub PeerServerAddresses: Vec<String>,
+    pub StreamStatus: PeeringStreamStatus,
+    pub Remote: PeeringRemoteInfo,
+    /// RFC 3339 timestamp, set once the peering is marked for deletion
+    pub DeletedAt: Option<String>,
+    pub CreateIndex: u64,
+    pub ModifyIndex: u64,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct PeeringGenerateTokenRequest {
+    /// Name the peer is known by in this cluster
+    pub PeerName: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Partition: String,
+    /// Addresses the peer should dial instead of the servers of this cluster, e.g. mesh
+    /// gateways
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub ServerExternalAddresses: Vec<String>,
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub Meta: HashMap<String, String>,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct PeeringEstablishRequest {
+    /// Name the peer is known by in this cluster
+    pub PeerName: String,
+    /// Token generated by the peer
+    pub PeeringToken: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Partition: String,
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub Meta: HashMap<String, String>,
+}
+
+#[derive(Default, Deserialize, Debug)]
+#[serde(default)]
+struct PeeringGenerateTokenResponse {
+    PeeringToken: String,
+}
+
+#[async_trait]
+pub trait Peerings {
+    async fn peering_generate_token(
+        &self,
+        req: &PeeringGenerateTokenRequest,
+        q: Option<&WriteOptions>,
+    ) -> Result<(String, WriteMeta)>;
+    async fn peering_establish(
+        &self,
+        req: &PeeringEstablishRequest,
+        q: Option<&WriteOptions>,
+    ) -> Result<((), WriteMeta)>;
+    async fn peering_read(
+        &self,
+        name: &str,
+        q: Option<&QueryOptions>,
+    ) -> Result<(Option<Peering>, QueryMeta)>;
+    async fn peering_list(&self, q: Option<&QueryOptions>) -> Result<(Vec<Peering>, QueryMeta)>;
+    async fn peering_delete(&self, name: &str, q: Option<&WriteOptions>)
+        -> Result<((), WriteMeta)>;
+}
+
+#[async_trait]
+impl Peerings for Client {
+    /// https://developer.hashicorp.com/consul/api-docs/peering#generate-a-peering-token
+    async fn peering_generate_token(
+        &self,
+        req: &PeeringGenerateTokenRequest,
+        q: Option<&WriteOptions>,
+    ) -> Result<(String, WriteMeta)> {
+        let (response, meta): (PeeringGenerateTokenResponse, _) = post(
+            "/v1/peering/token",
+            Some(req),
+            &self.config,
+            HashMap::new(),
+            q,
+        )
+        .await?;
+        Ok((response.PeeringToken, meta))
+    }
+
+    /// https://developer.hashicorp.com/consul/api-docs/peering#establish-a-peering-connection
+    async fn peering_establish(
+        &self,
+        req: &PeeringEstablishRequest,
+        q: Option<&WriteOptions>,
+    ) -> Result<((), WriteMeta)> {
+        post(
+            "/v1/peering/establish",
+            Some(req),
+            &self.config,
+            HashMap::new(),
+            q,
+        )
+        .await
+    }
+
+    /// https://developer.hashicorp.com/consul/api-docs/peering#read-a-peering-connection
+    async fn peering_read(
+        &self,
+        name: &str,
+        q: Option<&QueryOptions>,
+    ) -> Result<(Option<Peering>, QueryMeta)> {
+        let path = format!("/v1/peering/{}", name);
+        get_optional(&path, &self.config, HashMap::new(), q).await
+    }
+
+    /// https://developer.hashicorp.com/consul/api-docs/peering#list-all-peerings
+    async fn peering_list(&self, q: Option<&QueryOptions>) -> Result<(Vec<Peering>, QueryMeta)> {
+        get("/v1/peerings", &self.config, HashMap::new(), q).await
+    }
+
+    /// https://developer.hashicorp.com/consul/api-docs/peering#delete-a-peering-connection
+    ///
+    /// The peering is marked for deletion and removed once its resources are cleaned up.
+    async fn peering_delete(
+        &self,
+        name: &str,
+        q: Option<&WriteOptions>,
+    ) -> Result<((), WriteMeta)> {
+        let path = format!("/v1/peering/{}", name);
+        delete(&path, &self.config, HashMap::new(), q).await
+    }
+}
+
+const MIN_POLL_DELAY: Duration = Duration::from_secs(1);
+const MAX_POLL_DELAY: Duration = Duration::from_secs(10);
+
+/// Follows the state of a peering. The peering read endpoint doesn't support blocking
+/// queries, so the watch polls it, backing off while the state doesn't change.
+pub struct PeeringWatch {
+    client: Client,
+    name: String,
+    options: QueryOptions,
+    /// The peering last returned by `next`, if it was called
+    last: Option<Option<Peering>>,
+    delay: Duration,
+}
+
+impl PeeringWatch {
+    pub fn new(client: Client, name: &str) -> Self {
+        PeeringWatch {
+            client,
+            name: name.to_owned(),
+            options: QueryOptions::default(),
+            last: None,
+            delay: MIN_POLL_DELAY,
+        }
+    }
+
+    /// Options of the reads.
+    pub fn query_options(mut self, options: QueryOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Returns the peering as soon as its state differs from the one last returned, the
+    /// first call returns it right away. `None` means that the peering doesn't exist.
+    /// On error, the watch can be resumed by calling `next` again.
+    pub async fn next(&mut self) -> Result<Option<Peering>> {
+        loop {
+            let (peering, _) = self
+                .client
+                .peering_read(&self.name, Some(&self.options))
+                .await?;
+            let state = peering.as_ref().map(|p| p.PeeringState);
+            let last_state = self
+                .last
+                .as_ref()
+                .map(|last| last.as_ref().map(|p| p.PeeringState));
+            if last_state != Some(state) {
+                self.last = Some(peering.clone());
+                self.delay = MIN_POLL_DELAY;
+                return Ok(peering);
+            }
+            tokio::time::sleep(self.delay).await;
+            self.delay = (self.delay * 2).min(MAX_POLL_DELAY);
+        }
+    }
+
+    /// Waits until the peering is in one of `states`, returning right away if the one last
+    /// returned by `next` already is. Fails with `Error::PeeringTimeout` after `timeout`.
+    pub async fn wait_for(
+        &mut self,
+        states: &[PeeringState],
+        timeout: Duration,
+    ) -> Result<Peering> {
+        if let Some(Some(peering)) = &self.last {
+            if states.contains(&peering.PeeringState) {
+                return Ok(peering.clone());
+            }
+        }
+        let name = self.name.clone();
+        let wait = async {
+            loop {
+                if let Some(peering) = self.next().await? {
+                    if states.contains(&peering.PeeringState) {
+                        return Ok(peering);
+                    }
+                }
+            }
+        };
+        tokio::time::timeout(timeout, wait)
+            .await
+            .map_err(|_| Error::PeeringTimeout(name))?
+    }
+}
@@ -0,0 +1,250 @@
+use async_trait::async_trait;
+use std::collections::HashMap;
+
+use crate::errors::Result;
+use crate::health::ServiceEntry;
+use crate::request::{delete, get, get_vec, post, put};
+use crate::{Client, QueryMeta, QueryOptions, WriteMeta, WriteOptions};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Default, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct QueryFailoverTarget {
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Peer: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Datacenter: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Partition: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Namespace: String,
+}
+
+/// Where to send the query when no healthy instance is found in the local datacenter.
+#[derive(Clone, Default, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct QueryFailoverOptions {
+    /// Number of remote datacenters to try, ordered by estimated round trip time.
+    pub NearestN: i32,
+    /// Datacenters to try after the `NearestN` ones, in order.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub Datacenters: Vec<String>,
+    /// Replaces `NearestN` and `Datacenters`, can also target cluster peers.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub Targets: Vec<QueryFailoverTarget>,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct QueryDNSOptions {
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub TTL: String,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct ServiceQuery {
+    pub Service: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Namespace: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Partition: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Peer: String,
+    /// A node name, or `_agent` to sort the results by round trip time from the agent.
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Near: String,
+    pub Failover: QueryFailoverOptions,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub IgnoreCheckIDs: Vec<String>,
+    pub OnlyPassing: bool,
+    /// Tags that must be present, or absent when prefixed with `!`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub Tags: Vec<String>,
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub NodeMeta: HashMap<String, String>,
+    #[serde(skip_serializing_if = "HashMap::is_empty")]
+    pub ServiceMeta: HashMap<String, String>,
+    pub Connect: bool,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct QueryTemplate {
+    /// Only `name_prefix_match` is supported by Consul.
+    pub Type: String,
+    /// Applied to the query name, capture groups can be used in the query as `${match(N)}`.
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Regexp: String,
+    pub RemoveEmptyTags: bool,
+}
+
+impl QueryTemplate {
+    pub fn name_prefix_match(regexp: Option<&str>) -> Self {
+        QueryTemplate {
+            Type: String::from("name_prefix_match"),
+            Regexp: regexp.unwrap_or_default().to_owned(),
+            RemoveEmptyTags: false,
+        }
+    }
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct PreparedQueryDefinition {
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub ID: String,
+    pub Name: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Session: String,
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub Token: String,
+    pub Service: ServiceQuery,
+    pub DNS: QueryDNSOptions,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub Template: Option<QueryTemplate>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub CreateIndex: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ModifyIndex: Option<u64>,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct PreparedQueryExecuteResponse {
+    pub Service: String,
+    pub Namespace: String,
+    pub Nodes: Vec<ServiceEntry>,
+    pub DNS: QueryDNSOptions,
+    /// The datacenter that answered the query.
+    pub Datacenter: String,
+    /// Number of remote datacenters tried before `Datacenter`, 0 when answered locally.
+    pub Failovers: i32,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct PreparedQueryExplainResponse {
+    /// The query after template interpolation.
+    pub Query: PreparedQueryDefinition,
+}
+
+#[derive(Clone, Default, Serialize, Deserialize, Debug)]
+#[serde(default)]
+struct PreparedQueryID {
+    ID: String,
+}
+
+#[async_trait]
+pub trait PreparedQuery {
+    async fn query_create(
+        &self,
+        query: &PreparedQueryDefinition,
+        q: Option<&WriteOptions>,
+    ) -> Result<(String, WriteMeta)>;
+    async fn query_update(
+        &self,
+        query: &PreparedQueryDefinition,
+        q: Option<&WriteOptions>,
+    ) -> Result<((), WriteMeta)>;
+    async fn query_list(
+        &self,
+        q: Option<&QueryOptions>,
+    ) -> Result<(Vec<PreparedQueryDefinition>, QueryMeta)>;
+    async fn query_get(
+        &self,
+        id: &str,
+        q: Option<&QueryOptions>,
+    ) -> Result<(Option<PreparedQueryDefinition>, QueryMeta)>;
+    async fn query_delete(&self, id: &str, q: Option<&WriteOptions>) -> Result<((), WriteMeta)>;
+    async fn query_execute(
+        &self,
+        id_or_name: &str,
+        near: Option<&str>,
+        limit: Option<usize>,
+        q: Option<&QueryOptions>,
+    ) -> Result<(PreparedQueryExecuteResponse, QueryMeta)>;
+    async fn query_explain(
+        &self,
+        id_or_name: &str,
+        q: Option<&QueryOptions>,
+    ) -> Result<(PreparedQueryExplainResponse, QueryMeta)>;
+}
+
+#[async_trait]
+impl PreparedQuery for Client {
+    /// https://developer.hashicorp.com/consul/api-docs/query#create-prepared-query
+    async fn query_create(
+        &self,
+        query: &PreparedQueryDefinition,
+        q: Option<&WriteOptions>,
+    ) -> Result<(String, WriteMeta)> {
+        post("/v1/query", Some(query), &self.config, HashMap::new(), q)
+            .await
+            .map(|(id, meta): (PreparedQueryID, WriteMeta)| (id.ID, meta))
+    }
+
+    /// https://developer.hashicorp.com/consul/api-docs/query#update-prepared-query
+    async fn query_update(
+        &self,
+        query: &PreparedQueryDefinition,
+        q: Option<&WriteOptions>,
+    ) -> Result<((), WriteMeta)> {
+        let path = format!("/v1/query/{}", query.ID);
+        put(&path, Some(query), &self.config, HashMap::new(), q).await
+    }
+
+    /// https://developer.hashicorp.com/consul/api-docs/query#read-prepared-query
+    async fn query_list(
+        &self,
+        q: Option<&QueryOptions>,
+    ) -> Result<(Vec<PreparedQueryDefinition>, QueryMeta)> {
+        get("/v1/query", &self.config, HashMap::new(), q).await
+    }
+
+    /// https://developer.hashicorp.com/consul/api-docs/query#read-prepared-query-1
+    async fn query_get(
+        &self,
+        id: &str,
+        q: Option<&QueryOptions>,
+    ) -> Result<(Option<PreparedQueryDefinition>, QueryMeta)> {
+        let path = format!("/v1/query/{}", id);
+        let (queries, meta) = get_vec(&path, &self.config, HashMap::new(), q).await?;
+        Ok((queries.into_iter().next(), meta))
+    }
+
+    /// https://developer.hashicorp.com/consul/api-docs/query#delete-prepared-query
+    async fn query_delete(&self, id: &str, q: Option<&WriteOptions>) -> Result<((), WriteMeta)> {
+        let path = format!("/v1/query/{}", id);
+        delete(&path, &self.config, HashMap::new(), q).await
+    }
+
+    /// https://developer.hashicorp.com/consul/api-docs/query#execute-prepared-query
+    async fn query_execute(
+        &self,
+        id_or_name: &str,
+        near: Option<&str>,
+        limit: Option<usize>,
+        q: Option<&QueryOptions>,
+    ) -> Result<(PreparedQueryExecuteResponse, QueryMeta)> {
+        let mut params = HashMap::new();
+        if let Some(near) = near {
+            params.insert(String::from("near"), near.to_owned());
+        }
+        if let Some(limit) = limit {
+            params.insert(String::from("limit"), limit.to_string());
+        }
+        let path = format!("/v1/query/{}/execute", id_or_name);
+        get(&path, &self.config, params, q).await
+    }
+
+    /// https://developer.hashicorp.com/consul/api-docs/query#explain-prepared-query
+    async fn query_explain(
+        &self,
+        id_or_name: &str,
+        q: Option<&QueryOptions>,
+    ) -> Result<(PreparedQueryExplainResponse, QueryMeta)> {
+        let path = format!("/v1/query/{}/explain", id_or_name);
+        get(&path, &self.config, HashMap::new(), q).await
+    }
+}
@@ -0,0 +1,130 @@
+use std::time::Duration;
+
+use reqwest::StatusCode;
+use tokio::runtime::Handle;
+use tokio::task::JoinHandle;
+use tokio::time::{interval, MissedTickBehavior};
+
+use crate::agent::{Agent, RegisterAgentCheck, RegisterAgentService, TTLStatus};
+use crate::errors::{Error, Result};
+use crate::Client;
+
+/// Shorter TTLs would have the heartbeat flood the agent.
+const MIN_TTL: Duration = Duration::from_secs(1);
+
+/// A service registered with the local agent and kept alive by a TTL check.
+///
+/// A background task reports the result of the health callback every third of the TTL,
+/// and registers the service again when the agent no longer knows the check (e.g. after
+/// an agent restart). The service is deregistered by `shutdown`, or on a best effort
+/// basis when the handle is dropped inside a tokio runtime.
+pub struct ServiceRegistration {
+    client: Client,
+    service_id: String,
+    check_id: String,
+    heartbeat: Option<JoinHandle<()>>,
+}
+
+impl ServiceRegistration {
+    /// Registers `service` with an additional TTL check and starts the heartbeat.
+    /// An empty `service.ID` defaults to `service.Name`. Fails with `Error::InvalidTtl`
+    /// when `ttl` is shorter than a second.
+    pub async fn register<F>(
+        client: Client,
+        mut service: RegisterAgentService,
+        ttl: Duration,
+        health: F,
+    ) -> Result<ServiceRegistration>
+    where
+        F: Fn() -> (TTLStatus, Option<String>) + Send + 'static,
+    {
+        if ttl < MIN_TTL {
+            return Err(Error::InvalidTtl(ttl));
+        }
+        if service.ID.is_empty() {
+            service.ID = service.Name.clone();
+        }
+        let service_id = service.ID.clone();
+        let check_id = format!("service:{}:ttl", service_id);
+        service.Checks.push(
+            RegisterAgentCheck::ttl(&format!("{}ms", ttl.as_millis()))
+                .id(&check_id)
+                .name(&format!("Service '{}' TTL", service.Name)),
+        );
+        client.register_service(&service, false).await?;
+
+        let heartbeat = tokio::spawn(heartbeat(
+            client.clone(),
+            service,
+            check_id.clone(),
+            ttl / 3,
+            health,
+        ));
+        Ok(ServiceRegistration {
+            client,
+            service_id,
+            check_id,
+            heartbeat: Some(heartbeat),
+        })
+    }
+
+    pub fn service_id(&self) -> &str {
+        &self.service_id
+    }
+
+    pub fn check_id(&self) -> &str {
+        &self.check_id
+    }
+
+    /// Stops the heartbeat and deregisters the service.
+    pub async fn shutdown(mut self) -> Result<()> {
+        if let Some(heartbeat) = self.heartbeat.take() {
+            heartbeat.abort();
+        }
+        self.client.deregister_service(&self.service_id).await
+    }
+}
+
+impl Drop for ServiceRegistration {
+    fn drop(&mut self) {
+        if let Some(heartbeat) = self.heartbeat.take() {
+            heartbeat.abort();
+            if let Ok(runtime) = Handle::try_current() {
+                let client = self.client.clone();
+                let service_id = self.service_id.clone();
+                runtime.spawn(async move {
+                    let _ = client.deregister_service(&service_id).await;
+                });
+            }
+        }
+    }
+}
+
+async fn heartbeat<F>(
+    client: Client,
+    service: RegisterAgentService,
+    check_id: String,
+    period: Duration,
+    health: F,
+) where
+    F: Fn() -> (TTLStatus, Option<String>),
+{
+    let mut ticker = interval(period);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    loop {
+        ticker.tick().await;
+        let (status, notes) = health();
+        let sent = client
+            .ttl_check_send(status, &check_id, notes.as_deref())
+            .await;
+        if let Err(Error::ReqwestError(e)) = sent {
+            if e.status() == Some(StatusCode::NOT_FOUND)
+                && client.register_service(&service, false).await.is_ok()
+            {
+                let _ = client
+                    .ttl_check_send(status, &check_id, notes.as_deref())
+                    .await;
+            }
+        }
+    }
+}
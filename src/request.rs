@@ -6,114 +6,254 @@ use std::str;
 use std::str::FromStr;
 use std::time::Instant;
 
-use reqwest::header::HeaderValue;
-use reqwest::RequestBuilder;
+use reqwest::header::{HeaderMap, HeaderValue};
+use reqwest::{Body, RequestBuilder, Response};
 use reqwest::{Client as HttpClient, StatusCode};
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 
-use crate::errors::Result;
-use crate::{Config, QueryMeta, QueryOptions, WriteMeta, WriteOptions};
+use crate::errors::{Error, Result};
+use crate::{Config, ConsistencyMode, QueryMeta, QueryOptions, WriteMeta, WriteOptions};
 
-fn add_config_options(builder: RequestBuilder, config: &Config) -> RequestBuilder {
-    match &config.token {
+/// Sets the token of a request, the one of the options takes precedence over the config.
+fn add_config_options(
+    builder: RequestBuilder,
+    config: &Config,
+    token: Option<&String>,
+) -> RequestBuilder {
+    match token.or(config.token.as_ref()) {
         Some(val) => builder.header("X-Consul-Token", val),
         None => builder,
     }
 }
 
-pub async fn get_vec<R: DeserializeOwned>(
-    path: &str,
+/// Sets the datacenter, namespace and partition of a request, the options take
+/// precedence over the config.
+fn add_scope(
+    params: &mut HashMap<String, String>,
     config: &Config,
-    mut params: HashMap<String, String>,
-    options: Option<&QueryOptions>,
-) -> Result<(Vec<R>, QueryMeta)> {
-    let datacenter: Option<&String> = options
-        .and_then(|o| o.datacenter.as_ref())
-        .or(config.datacenter.as_ref());
-    if let Some(dc) = datacenter {
-        params.insert(String::from("dc"), dc.to_owned());
+    datacenter: Option<&String>,
+    namespace: Option<&String>,
+    partition: Option<&String>,
+) {
+    let scope = [
+        ("dc", datacenter.or(config.datacenter.as_ref())),
+        ("ns", namespace.or(config.namespace.as_ref())),
+        ("partition", partition.or(config.partition.as_ref())),
+    ];
+    for (name, value) in scope.iter() {
+        if let Some(value) = value {
+            params.insert(String::from(*name), value.to_string());
+        }
+    }
+}
+
+/// Sets the `relay-factor` of the keyring operations, Consul accepts 0 to 5.
+pub fn add_relay_factor(
+    params: &mut HashMap<String, String>,
+    relay_factor: Option<u8>,
+) -> Result<()> {
+    match relay_factor {
+        Some(relay_factor) if relay_factor > 5 => Err(Error::InvalidRelayFactor(relay_factor)),
+        Some(relay_factor) => {
+            params.insert(String::from("relay-factor"), relay_factor.to_string());
+            Ok(())
+        }
+        None => Ok(()),
     }
+}
+
+fn add_query_options(
+    params: &mut HashMap<String, String>,
+    config: &Config,
+    options: Option<&QueryOptions>,
+) {
+    add_scope(
+        params,
+        config,
+        options.and_then(|o| o.datacenter.as_ref()),
+        options.and_then(|o| o.namespace.as_ref()),
+        options.and_then(|o| o.partition.as_ref()),
+    );
     if let Some(options) = options {
         if let Some(index) = options.wait_index {
             params.insert(String::from("index"), index.to_string());
         }
+        if let Some(hash) = &options.wait_hash {
+            params.insert(String::from("hash"), hash.to_owned());
+        }
         if let Some(wait_time) = options.wait_time {
             params.insert(String::from("wait"), format!("{}s", wait_time.as_secs()));
         }
+        if let Some(peer) = &options.peer {
+            params.insert(String::from("peer"), peer.to_owned());
+        }
+        match options.consistency {
+            Some(ConsistencyMode::Consistent) => {
+                params.insert(String::from("consistent"), String::new());
+            }
+            Some(ConsistencyMode::Stale) => {
+                params.insert(String::from("stale"), String::new());
+            }
+            None => {}
+        }
     }
+}
+
+fn query_meta(headers: &HeaderMap, start: Instant) -> Result<QueryMeta> {
+    let last_index = headers
+        .get("X-Consul-Index")
+        .and_then(|value: &HeaderValue| value.to_str().ok())
+        .map(u64::from_str)
+        .transpose()?;
+    let last_content_hash = headers
+        .get("X-Consul-ContentHash")
+        .and_then(|value: &HeaderValue| value.to_str().ok())
+        .map(String::from);
+    Ok(QueryMeta {
+        last_index,
+        last_content_hash,
+        request_time: Instant::now() - start,
+    })
+}
+
+pub async fn get_vec<R: DeserializeOwned>(
+    path: &str,
+    config: &Config,
+    mut params: HashMap<String, String>,
+    options: Option<&QueryOptions>,
+) -> Result<(Vec<R>, QueryMeta)> {
+    add_query_options(&mut params, config, options);
     let url_str = format!("{}{}", config.address, path);
     let url = Url::parse_with_params(&url_str, params.iter())?;
     let start = Instant::now();
-    let request_builder = add_config_options(config.http_client.get(url), config);
+    let request_builder = add_config_options(
+        config.http_client.get(url),
+        config,
+        options.and_then(|o| o.token.as_ref()),
+    );
     let response = request_builder.send().await?;
     let code = response.status();
-    let last_index = response
-        .headers()
-        .get("X-Consul-Index")
-        .and_then(|value: &HeaderValue| value.to_str().ok())
-        .map(u64::from_str)
-        .transpose()?;
     if code == StatusCode::NOT_FOUND {
-        return Ok((
-            Vec::new(),
-            QueryMeta {
-                last_index,
-                request_time: Instant::now() - start,
-            },
-        ));
+        return Ok((Vec::new(), query_meta(response.headers(), start)?));
     }
     response.error_for_status_ref()?;
+    let meta = query_meta(response.headers(), start)?;
     let payload: Vec<_> = response.json().await?;
-    Ok((
-        payload,
-        QueryMeta {
-            last_index,
-            request_time: Instant::now() - start,
-        },
-    ))
+    Ok((payload, meta))
 }
 
-pub async fn get<R: DeserializeOwned>(
+/// Like `get`, for the endpoints which answer 404 when the object doesn't exist.
+pub async fn get_optional<R: DeserializeOwned>(
     path: &str,
     config: &Config,
     mut params: HashMap<String, String>,
     options: Option<&QueryOptions>,
-) -> Result<(R, QueryMeta)> {
-    let datacenter: Option<&String> = options
-        .and_then(|o| o.datacenter.as_ref())
-        .or(config.datacenter.as_ref());
-    if let Some(dc) = datacenter {
-        params.insert(String::from("dc"), dc.to_owned());
-    }
-    if let Some(options) = options {
-        if let Some(index) = options.wait_index {
-            params.insert(String::from("index"), index.to_string());
-        }
-        if let Some(wait_time) = options.wait_time {
-            params.insert(String::from("wait"), format!("{}s", wait_time.as_secs()));
-        }
-    }
+) -> Result<(Option<R>, QueryMeta)> {
+    add_query_options(&mut params, config, options);
     let url_str = format!("{}{}", config.address, path);
     let url = Url::parse_with_params(&url_str, params.iter())?;
     let start = Instant::now();
-    let request_builder = add_config_options(config.http_client.get(url), config);
+    let request_builder = add_config_options(
+        config.http_client.get(url),
+        config,
+        options.and_then(|o| o.token.as_ref()),
+    );
     let response = request_builder.send().await?;
+    if response.status() == StatusCode::NOT_FOUND {
+        return Ok((None, query_meta(response.headers(), start)?));
+    }
     response.error_for_status_ref()?;
-    let last_index = response
-        .headers()
-        .get("X-Consul-Index")
-        .and_then(|value: &HeaderValue| value.to_str().ok())
-        .map(u64::from_str)
-        .transpose()?;
+    let meta = query_meta(response.headers(), start)?;
     let payload = response.json().await?;
-    Ok((
-        payload,
-        QueryMeta {
-            last_index,
-            request_time: Instant::now() - start,
-        },
-    ))
+    Ok((Some(payload), meta))
+}
+
+/// Returns the response as is, for endpoints whose body isn't JSON or is too large
+/// to be buffered.
+pub async fn get_response(
+    path: &str,
+    config: &Config,
+    mut params: HashMap<String, String>,
+    options: Option<&QueryOptions>,
+) -> Result<(Response, QueryMeta)> {
+    add_query_options(&mut params, config, options);
+    let url_str = format!("{}{}", config.address, path);
+    let url = Url::parse_with_params(&url_str, params.iter())?;
+    let start = Instant::now();
+    let request_builder = add_config_options(
+        config.http_client.get(url),
+        config,
+        options.and_then(|o| o.token.as_ref()),
+    );
+    let response = request_builder.send().await?.error_for_status()?;
+    let meta = query_meta(response.headers(), start)?;
+    Ok((response, meta))
+}
+
+pub async fn get<R: DeserializeOwned>(
+    path: &str,
+    config: &Config,
+    params: HashMap<String, String>,
+    options: Option<&QueryOptions>,
+) -> Result<(R, QueryMeta)> {
+    get_with_status(path, config, params, options, &[]).await
+}
+
+/// Like `get`, but the body of a response whose status is in `accepted` is decoded
+/// instead of being turned into an error. The agent-local health endpoints report
+/// warning and critical services this way.
+pub async fn get_with_status<R: DeserializeOwned>(
+    path: &str,
+    config: &Config,
+    params: HashMap<String, String>,
+    options: Option<&QueryOptions>,
+    accepted: &[StatusCode],
+) -> Result<(R, QueryMeta)> {
+    let req = |http_client: &HttpClient, url: Url| -> RequestBuilder { http_client.get(url) };
+    query_with_body(path, config, params, options, accepted, req).await
+}
+
+/// Like `get`, for the read endpoints which take their parameters as a JSON body.
+pub async fn post_query<T: Serialize, R: DeserializeOwned>(
+    path: &str,
+    body: &T,
+    config: &Config,
+    params: HashMap<String, String>,
+    options: Option<&QueryOptions>,
+) -> Result<(R, QueryMeta)> {
+    let req =
+        |http_client: &HttpClient, url: Url| -> RequestBuilder { http_client.post(url).json(body) };
+    query_with_body(path, config, params, options, &[], req).await
+}
+
+async fn query_with_body<R: DeserializeOwned, F>(
+    path: &str,
+    config: &Config,
+    mut params: HashMap<String, String>,
+    options: Option<&QueryOptions>,
+    accepted: &[StatusCode],
+    req: F,
+) -> Result<(R, QueryMeta)>
+where
+    F: FnOnce(&HttpClient, Url) -> RequestBuilder,
+{
+    add_query_options(&mut params, config, options);
+    let url_str = format!("{}{}", config.address, path);
+    let url = Url::parse_with_params(&url_str, params.iter())?;
+    let start = Instant::now();
+    let request_builder = add_config_options(
+        req(&config.http_client, url),
+        config,
+        options.and_then(|o| o.token.as_ref()),
+    );
+    let response = request_builder.send().await?;
+    if !accepted.contains(&response.status()) {
+        response.error_for_status_ref()?;
+    }
+    let meta = query_meta(response.headers(), start)?;
+    let payload = response.json().await?;
+    Ok((payload, meta))
 }
 
 pub async fn delete<R: DeserializeOwned + 'static>(
@@ -126,6 +266,17 @@ pub async fn delete<R: DeserializeOwned + 'static>(
     write_with_body(path, None as Option<&()>, config, params, options, req).await
 }
 
+pub async fn delete_with_body<T: Serialize, R: DeserializeOwned + 'static>(
+    path: &str,
+    body: Option<&T>,
+    config: &Config,
+    params: HashMap<String, String>,
+    options: Option<&WriteOptions>,
+) -> Result<(R, WriteMeta)> {
+    let req = |http_client: &HttpClient, url: Url| -> RequestBuilder { http_client.delete(url) };
+    write_with_body(path, body, config, params, options, req).await
+}
+
 pub async fn put<T: Serialize, R: DeserializeOwned + 'static>(
     path: &str,
     body: Option<&T>,
@@ -137,6 +288,32 @@ pub async fn put<T: Serialize, R: DeserializeOwned + 'static>(
     write_with_body(path, body, config, params, options, req).await
 }
 
+pub async fn post<T: Serialize, R: DeserializeOwned + 'static>(
+    path: &str,
+    body: Option<&T>,
+    config: &Config,
+    params: HashMap<String, String>,
+    options: Option<&WriteOptions>,
+) -> Result<(R, WriteMeta)> {
+    let req = |http_client: &HttpClient, url: Url| -> RequestBuilder { http_client.post(url) };
+    write_with_body(path, body, config, params, options, req).await
+}
+
+/// Like `put`, but sends `body` as is instead of encoding it as JSON.
+/// The body can be streamed, see `reqwest::Body::wrap_stream`.
+pub async fn put_body<B: Into<Body>, R: DeserializeOwned + 'static>(
+    path: &str,
+    body: B,
+    config: &Config,
+    params: HashMap<String, String>,
+    options: Option<&WriteOptions>,
+) -> Result<(R, WriteMeta)> {
+    let req = move |http_client: &HttpClient, url: Url| -> RequestBuilder {
+        http_client.put(url).body(body)
+    };
+    write_with_body(path, None as Option<&()>, config, params, options, req).await
+}
+
 async fn write_with_body<T: Serialize, R: DeserializeOwned + 'static, F>(
     path: &str,
     body: Option<&T>,
@@ -146,15 +323,16 @@ async fn write_with_body<T: Serialize, R: DeserializeOwned + 'static, F>(
     req: F,
 ) -> Result<(R, WriteMeta)>
 where
-    F: Fn(&HttpClient, Url) -> RequestBuilder,
+    F: FnOnce(&HttpClient, Url) -> RequestBuilder,
 {
     let start = Instant::now();
-    let datacenter: Option<&String> = options
-        .and_then(|o| o.datacenter.as_ref())
-        .or(config.datacenter.as_ref());
-    if let Some(dc) = datacenter {
-        params.insert(String::from("dc"), dc.to_owned());
-    }
+    add_scope(
+        &mut params,
+        config,
+        options.and_then(|o| o.datacenter.as_ref()),
+        options.and_then(|o| o.namespace.as_ref()),
+        options.and_then(|o| o.partition.as_ref()),
+    );
     let url_str = format!("{}{}", config.address, path);
     let url = Url::parse_with_params(&url_str, params.iter())?;
     let builder = req(&config.http_client, url);
@@ -163,7 +341,7 @@ where
     } else {
         builder
     };
-    let builder = add_config_options(builder, config);
+    let builder = add_config_options(builder, config, options.and_then(|o| o.token.as_ref()));
     let response = builder.send().await?.error_for_status()?;
     if TypeId::of::<R>() == ().type_id() || matches!(response.content_length(), Some(0)) {
         return Ok((
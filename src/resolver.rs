@@ -0,0 +1,156 @@
+use std::future::Future;
+use std::io;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use hyper::client::connect::dns::Name;
+use hyper::service::Service;
+use rand::seq::SliceRandom;
+use reqwest::dns::{Addrs, Resolve, Resolving};
+
+use crate::discovery::Instance;
+use crate::errors::Result;
+use crate::health::Health;
+use crate::{Client, QueryOptions};
+
+type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// A service name in the Consul DNS form `[<tag>.]<service>.service[.<datacenter>].<domain>`.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ServiceName {
+    pub service: String,
+    pub tag: Option<String>,
+    pub datacenter: Option<String>,
+}
+
+impl ServiceName {
+    /// Returns `None` when `name` is not a service name under `domain`.
+    pub fn parse(name: &str, domain: &str) -> Option<ServiceName> {
+        let name = name.trim_end_matches('.').to_ascii_lowercase();
+        let domain = domain.trim_matches('.').to_ascii_lowercase();
+        let prefix = name.strip_suffix(&domain)?.strip_suffix('.')?;
+        let labels: Vec<&str> = prefix.split('.').collect();
+        let service_label = labels.iter().rposition(|l| *l == "service")?;
+        let datacenter = match &labels[service_label + 1..] {
+            [] => None,
+            [dc] => Some(dc.to_string()),
+            _ => return None,
+        };
+        let (tag, service) = match &labels[..service_label] {
+            [service] => (None, service),
+            [tag, service] => (Some(tag.to_string()), service),
+            _ => return None,
+        };
+        if service.is_empty() {
+            return None;
+        }
+        Some(ServiceName {
+            service: service.to_string(),
+            tag,
+            datacenter,
+        })
+    }
+}
+
+/// Resolves Consul service names through the HTTP API, other names through the system resolver.
+///
+/// The HTTP clients only use the IP addresses, the port still comes from the URL.
+#[derive(Clone, Debug)]
+pub struct ConsulResolver {
+    client: Client,
+    domain: String,
+    passing_only: bool,
+}
+
+impl ConsulResolver {
+    pub fn new(client: Client) -> Self {
+        ConsulResolver {
+            client,
+            domain: String::from("consul"),
+            passing_only: true,
+        }
+    }
+
+    /// The DNS domain of the cluster, `consul` by default.
+    pub fn domain(mut self, domain: &str) -> Self {
+        self.domain = domain.to_owned();
+        self
+    }
+
+    /// Also returns instances with a warning check, only passing ones are returned by default.
+    pub fn passing_only(mut self, passing_only: bool) -> Self {
+        self.passing_only = passing_only;
+        self
+    }
+
+    /// The healthy instances of a service name, in random order.
+    pub async fn lookup(&self, name: &ServiceName) -> Result<Vec<SocketAddr>> {
+        let options = QueryOptions {
+            datacenter: name.datacenter.clone(),
+            ..Default::default()
+        };
+        let (entries, _) = self
+            .client
+            .service(
+                &name.service,
+                name.tag.as_deref(),
+                self.passing_only,
+                Some(&options),
+            )
+            .await?;
+        let mut addrs: Vec<SocketAddr> = entries
+            .iter()
+            .filter_map(Instance::from_entry)
+            .map(|i| i.address)
+            .collect();
+        addrs.shuffle(&mut rand::thread_rng());
+        Ok(addrs)
+    }
+
+    async fn resolve_name(self, name: String) -> std::result::Result<Vec<SocketAddr>, BoxError> {
+        match ServiceName::parse(&name, &self.domain) {
+            Some(service) => {
+                let addrs = self.lookup(&service).await?;
+                if addrs.is_empty() {
+                    return Err(Box::new(io::Error::new(
+                        io::ErrorKind::NotFound,
+                        format!("no healthy instance of {}", name),
+                    )));
+                }
+                Ok(addrs)
+            }
+            None => Ok(tokio::net::lookup_host((name.as_str(), 0)).await?.collect()),
+        }
+    }
+}
+
+impl Resolve for ConsulResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let resolver = self.clone();
+        Box::pin(async move {
+            let addrs = resolver.resolve_name(name.as_str().to_owned()).await?;
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+/// Allows the resolver to be used by `hyper::client::HttpConnector::new_with_resolver`.
+impl Service<Name> for ConsulResolver {
+    type Response = std::vec::IntoIter<SocketAddr>;
+    type Error = BoxError;
+    type Future =
+        Pin<Box<dyn Future<Output = std::result::Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, _: &mut Context<'_>) -> Poll<std::result::Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, name: Name) -> Self::Future {
+        let resolver = self.clone();
+        Box::pin(async move {
+            let addrs = resolver.resolve_name(name.as_str().to_owned()).await?;
+            Ok(addrs.into_iter())
+        })
+    }
+}
@@ -0,0 +1,91 @@
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::io::Read;
+
+use crate::errors::{Error, Result};
+use crate::request::{get_response, put_body};
+use crate::{Client, QueryMeta, QueryOptions, WriteMeta, WriteOptions};
+use flate2::read::GzDecoder;
+use reqwest::Body;
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncRead, AsyncWrite, AsyncWriteExt};
+use tokio_util::io::ReaderStream;
+
+/// Content of the `meta.json` file of a snapshot archive.
+#[derive(Clone, Default, Eq, PartialEq, Serialize, Deserialize, Debug)]
+#[serde(default)]
+pub struct SnapshotMeta {
+    pub Version: u32,
+    pub ID: String,
+    /// Raft index of the last log included in the snapshot
+    pub Index: u64,
+    pub Term: u64,
+    pub ConfigurationIndex: u64,
+    /// Size of the state, in bytes
+    pub Size: u64,
+}
+
+/// Reads the metadata of a snapshot archive, as written by `Snapshot::snapshot_save`,
+/// without restoring it.
+pub fn inspect<R: Read>(archive: R) -> Result<SnapshotMeta> {
+    let mut archive = tar::Archive::new(GzDecoder::new(archive));
+    for entry in archive.entries()? {
+        let entry = entry?;
+        if entry.path()?.as_os_str() == "meta.json" {
+            return Ok(serde_json::from_reader(entry)?);
+        }
+    }
+    Err(Error::SnapshotMissingMeta)
+}
+
+#[async_trait]
+pub trait Snapshot {
+    async fn snapshot_save<W: AsyncWrite + Unpin + Send>(
+        &self,
+        writer: &mut W,
+        stale: bool,
+        q: Option<&QueryOptions>,
+    ) -> Result<(u64, QueryMeta)>;
+    async fn snapshot_restore<R: AsyncRead + Send + Sync + 'static>(
+        &self,
+        reader: R,
+        q: Option<&WriteOptions>,
+    ) -> Result<((), WriteMeta)>;
+}
+
+#[async_trait]
+impl Snapshot for Client {
+    /// https://developer.hashicorp.com/consul/api-docs/snapshot#generate-snapshot
+    ///
+    /// Streams the archive to `writer` and returns its size. With `stale`, any server
+    /// can generate the snapshot instead of the leader.
+    async fn snapshot_save<W: AsyncWrite + Unpin + Send>(
+        &self,
+        writer: &mut W,
+        stale: bool,
+        q: Option<&QueryOptions>,
+    ) -> Result<(u64, QueryMeta)> {
+        let mut params = HashMap::new();
+        if stale {
+            params.insert(String::from("stale"), String::new());
+        }
+        let (mut response, meta) = get_response("/v1/snapshot", &self.config, params, q).await?;
+        let mut written = 0;
+        while let Some(chunk) = response.chunk().await? {
+            writer.write_all(&chunk).await?;
+            written += chunk.len() as u64;
+        }
+        writer.flush().await?;
+        Ok((written, meta))
+    }
+
+    /// https://developer.hashicorp.com/consul/api-docs/snapshot#restore-snapshot
+    async fn snapshot_restore<R: AsyncRead + Send + Sync + 'static>(
+        &self,
+        reader: R,
+        q: Option<&WriteOptions>,
+    ) -> Result<((), WriteMeta)> {
+        let body = Body::wrap_stream(ReaderStream::new(reader));
+        put_body("/v1/snapshot", body, &self.config, HashMap::new(), q).await
+    }
+}
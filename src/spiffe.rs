@@ -0,0 +1,223 @@
+use std::fmt;
+use std::str::FromStr;
+
+use crate::connect_ca::CARootList;
+use crate::errors::{Error, Result};
+
+/// A Connect identity, as found in the URI SAN of the certificates issued by the
+/// Connect CA.
+///
+/// The partition segment is only present in Consul Enterprise, it is omitted for the
+/// `default` partition.
+#[derive(Clone, Eq, Hash, PartialEq, Debug)]
+pub enum SpiffeId {
+    /// `spiffe://<trust domain>[/ap/<partition>]/ns/<namespace>/dc/<datacenter>/svc/<service>`
+    Service {
+        trust_domain: String,
+        partition: Option<String>,
+        namespace: String,
+        datacenter: String,
+        service: String,
+    },
+    /// `spiffe://<trust domain>[/ap/<partition>]/agent/client/dc/<datacenter>/id/<node id>`
+    Agent {
+        trust_domain: String,
+        partition: Option<String>,
+        datacenter: String,
+        node_id: String,
+    },
+    /// `spiffe://<trust domain>[/ap/<partition>]/gateway/mesh/dc/<datacenter>`
+    MeshGateway {
+        trust_domain: String,
+        partition: Option<String>,
+        datacenter: String,
+    },
+}
+
+impl SpiffeId {
+    pub fn service(trust_domain: &str, namespace: &str, datacenter: &str, service: &str) -> Self {
+        SpiffeId::Service {
+            trust_domain: trust_domain.to_owned(),
+            partition: None,
+            namespace: namespace.to_owned(),
+            datacenter: datacenter.to_owned(),
+            service: service.to_owned(),
+        }
+    }
+
+    pub fn trust_domain(&self) -> &str {
+        match self {
+            SpiffeId::Service { trust_domain, .. }
+            | SpiffeId::Agent { trust_domain, .. }
+            | SpiffeId::MeshGateway { trust_domain, .. } => trust_domain,
+        }
+    }
+
+    pub fn partition(&self) -> Option<&str> {
+        match self {
+            SpiffeId::Service { partition, .. }
+            | SpiffeId::Agent { partition, .. }
+            | SpiffeId::MeshGateway { partition, .. } => partition.as_deref(),
+        }
+    }
+
+    pub fn datacenter(&self) -> &str {
+        match self {
+            SpiffeId::Service { datacenter, .. }
+            | SpiffeId::Agent { datacenter, .. }
+            | SpiffeId::MeshGateway { datacenter, .. } => datacenter,
+        }
+    }
+
+    /// The service name, for service identities.
+    pub fn service_name(&self) -> Option<&str> {
+        match self {
+            SpiffeId::Service { service, .. } => Some(service),
+            _ => None,
+        }
+    }
+
+    /// Trust domains are host names, they are compared regardless of case.
+    pub fn is_trusted(&self, trust_domain: &str) -> bool {
+        self.trust_domain().eq_ignore_ascii_case(trust_domain)
+    }
+
+    /// Whether both are the same identity. Trust domains are compared regardless of
+    /// case, and the `default` partition matches an omitted one.
+    pub fn matches(&self, other: &SpiffeId) -> bool {
+        self.normalized() == other.normalized()
+    }
+
+    fn normalized(&self) -> SpiffeId {
+        let mut id = self.clone();
+        match &mut id {
+            SpiffeId::Service {
+                trust_domain,
+                partition,
+                ..
+            }
+            | SpiffeId::Agent {
+                trust_domain,
+                partition,
+                ..
+            }
+            | SpiffeId::MeshGateway {
+                trust_domain,
+                partition,
+                ..
+            } => {
+                *trust_domain = trust_domain.to_ascii_lowercase();
+                if partition.as_deref() == Some("default") {
+                    *partition = None;
+                }
+            }
+        }
+        id
+    }
+
+    /// Fails unless the identity belongs to the trust domain of the CA roots.
+    pub fn validate(&self, roots: &CARootList) -> Result<()> {
+        if self.is_trusted(&roots.TrustDomain) {
+            Ok(())
+        } else {
+            Err(Error::InvalidSpiffeId(format!(
+                "{} is not in the trust domain {}",
+                self, roots.TrustDomain
+            )))
+        }
+    }
+
+    /// Extracts the identity from the URI SAN of a DER encoded certificate.
+    #[cfg(feature = "connect")]
+    pub fn from_certificate(der: &[u8]) -> Result<SpiffeId> {
+        use x509_parser::extensions::GeneralName;
+
+        let (_, cert) = x509_parser::parse_x509_certificate(der)
+            .map_err(|e| Error::InvalidCertificate(e.to_string()))?;
+        let san = cert
+            .subject_alternative_name()
+            .map_err(|e| Error::InvalidCertificate(e.to_string()))?;
+        let uri = san
+            .iter()
+            .flat_map(|san| san.value.general_names.iter())
+            .find_map(|name| match name {
+                GeneralName::URI(uri) if uri.starts_with("spiffe://") => Some(*uri),
+                _ => None,
+            })
+            .ok_or_else(|| Error::InvalidCertificate(String::from("no SPIFFE ID")))?;
+        uri.parse()
+    }
+}
+
+impl FromStr for SpiffeId {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<SpiffeId> {
+        let invalid = || Error::InvalidSpiffeId(s.to_owned());
+        let rest = s.strip_prefix("spiffe://").ok_or_else(invalid)?;
+        let mut segments = rest.split('/');
+        let trust_domain = segments
+            .next()
+            .filter(|td| !td.is_empty())
+            .ok_or_else(invalid)?;
+        let mut segments: Vec<&str> = segments.collect();
+        if segments.iter().any(|segment| segment.is_empty()) {
+            return Err(invalid());
+        }
+        let partition = match segments.as_slice() {
+            ["ap", partition, ..] => {
+                let partition = partition.to_string();
+                segments.drain(..2);
+                Some(partition)
+            }
+            _ => None,
+        };
+        let trust_domain = trust_domain.to_owned();
+        match segments.as_slice() {
+            ["ns", namespace, "dc", datacenter, "svc", service] => Ok(SpiffeId::Service {
+                trust_domain,
+                partition,
+                namespace: namespace.to_string(),
+                datacenter: datacenter.to_string(),
+                service: service.to_string(),
+            }),
+            ["agent", "client", "dc", datacenter, "id", node_id] => Ok(SpiffeId::Agent {
+                trust_domain,
+                partition,
+                datacenter: datacenter.to_string(),
+                node_id: node_id.to_string(),
+            }),
+            ["gateway", "mesh", "dc", datacenter] => Ok(SpiffeId::MeshGateway {
+                trust_domain,
+                partition,
+                datacenter: datacenter.to_string(),
+            }),
+            _ => Err(invalid()),
+        }
+    }
+}
+
+impl fmt::Display for SpiffeId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "spiffe://{}", self.trust_domain())?;
+        if let Some(partition) = self.partition() {
+            write!(f, "/ap/{}", partition)?;
+        }
+        match self {
+            SpiffeId::Service {
+                namespace,
+                datacenter,
+                service,
+                ..
+            } => write!(f, "/ns/{}/dc/{}/svc/{}", namespace, datacenter, service),
+            SpiffeId::Agent {
+                datacenter,
+                node_id,
+                ..
+            } => write!(f, "/agent/client/dc/{}/id/{}", datacenter, node_id),
+            SpiffeId::MeshGateway { datacenter, .. } => {
+                write!(f, "/gateway/mesh/dc/{}", datacenter)
+            }
+        }
+    }
+}
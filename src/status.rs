@@ -0,0 +1,27 @@
+use async_trait::async_trait;
+use std::collections::HashMap;
+
+use crate::errors::Result;
+use crate::request::get;
+use crate::{Client, QueryMeta, QueryOptions};
+
+#[async_trait]
+pub trait Status {
+    async fn leader(&self, q: Option<&QueryOptions>) -> Result<(String, QueryMeta)>;
+    async fn peers(&self, q: Option<&QueryOptions>) -> Result<(Vec<String>, QueryMeta)>;
+}
+
+#[async_trait]
+impl Status for Client {
+    /// https://developer.hashicorp.com/consul/api-docs/status#get-raft-leader
+    ///
+    /// Returns the Raft address of the leader, empty while no leader is elected.
+    async fn leader(&self, q: Option<&QueryOptions>) -> Result<(String, QueryMeta)> {
+        get("/v1/status/leader", &self.config, HashMap::new(), q).await
+    }
+
+    /// https://developer.hashicorp.com/consul/api-docs/status#list-raft-peers
+    async fn peers(&self, q: Option<&QueryOptions>) -> Result<(Vec<String>, QueryMeta)> {
+        get("/v1/status/peers", &self.config, HashMap::new(), q).await
+    }
+}
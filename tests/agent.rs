@@ -1,10 +1,15 @@
-use consul::agent::{Agent, RegisterAgentCheck, RegisterAgentService};
-use consul::{Client, Config};
+mod common;
+
+use consul::agent::{
+    diff_members, Agent, AgentMember, AgentServiceConnectProxyConfig, MemberStatus,
+    RegisterAgentCheck, RegisterAgentService, Upstream,
+};
+use consul::QueryOptions;
+use std::time::Duration;
 
 #[tokio::test]
 async fn test_service() {
-    let config = Config::new().unwrap();
-    let client = Client::new(config);
+    let client = common::client().await;
     client
         .register_service(
             &RegisterAgentService {
@@ -23,13 +28,113 @@ async fn test_service() {
         .await
         .unwrap();
     assert!(list.contains_key("test"));
+    let (service, _) = client.agent_service("test", None).await.unwrap();
+    assert_eq!(service.Port, 11451);
+    let (health, _) = client.health_service_by_id("test", None).await.unwrap();
+    assert_eq!(health.AggregatedStatus, "passing");
+    let (by_name, _) = client.health_service_by_name("test", None).await.unwrap();
+    assert_eq!(by_name.len(), 1);
+    let (unknown, _) = client
+        .health_service_by_name("test-unknown", None)
+        .await
+        .unwrap();
+    assert!(unknown.is_empty());
     client.deregister_service("test").await.unwrap();
 }
 
+#[tokio::test]
+async fn test_health_service_by_name_not_found() {
+    use consul::{Client, Config};
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = format!("http://{}", listener.local_addr().unwrap());
+    let client = Client::new(Config::new_from_addr(&address, None).unwrap());
+    let lookup = tokio::spawn(async move { client.health_service_by_name("unknown", None).await });
+    let request = common::serve_once_with_status(&listener, "404 Not Found", "[]").await;
+    assert!(request.starts_with("GET /v1/agent/health/service/name/unknown"));
+    assert!(lookup.await.unwrap().unwrap().0.is_empty());
+}
+
+#[tokio::test]
+async fn test_service_blocking_hash() {
+    let client = common::client().await;
+    client
+        .register_service(
+            &RegisterAgentService {
+                Name: "test-hash".to_string(),
+                ID: "test-hash".to_string(),
+                Port: 11452,
+                ..Default::default()
+            },
+            false,
+        )
+        .await
+        .unwrap();
+    let (_, meta) = client.agent_service("test-hash", None).await.unwrap();
+    let hash = meta.last_content_hash.unwrap();
+    let q = QueryOptions {
+        wait_hash: Some(hash.clone()),
+        wait_time: Some(Duration::from_secs(1)),
+        ..Default::default()
+    };
+    let (_, meta) = client.agent_service("test-hash", Some(&q)).await.unwrap();
+    assert_eq!(meta.last_content_hash, Some(hash));
+    client.deregister_service("test-hash").await.unwrap();
+}
+
+#[tokio::test]
+async fn test_service_with_sidecar() {
+    let client = common::client().await;
+    let reg = RegisterAgentService::new("test-sidecar")
+        .id("test-sidecar")
+        .port(11453)
+        .check(RegisterAgentCheck::ttl("30s").id("test-sidecar-ttl"))
+        .check(RegisterAgentCheck::tcp("127.0.0.1:11453", "10s").id("test-sidecar-tcp"))
+        .sidecar(RegisterAgentService::default().proxy(
+            AgentServiceConnectProxyConfig::default().upstream(Upstream::new("upstream", 9191)),
+        ));
+    client.register_service(&reg, false).await.unwrap();
+    let (proxy, _) = client
+        .agent_service("test-sidecar-sidecar-proxy", None)
+        .await
+        .unwrap();
+    assert_eq!(proxy.Kind, "connect-proxy");
+    let proxy_config = proxy.Proxy.unwrap();
+    assert_eq!(proxy_config.DestinationServiceName, "test-sidecar");
+    assert_eq!(proxy_config.Upstreams[0].DestinationName, "upstream");
+    let checks = client
+        .checks(Some(r#"ServiceID == "test-sidecar""#))
+        .await
+        .unwrap();
+    assert_eq!(checks.len(), 2);
+    client.deregister_service("test-sidecar").await.unwrap();
+}
+
+#[test]
+fn test_register_service_serialization() {
+    let reg = RegisterAgentService::new("web")
+        .address("10.0.0.1")
+        .port(8080)
+        .tag("v1")
+        .tagged_address("lan_ipv6", "::1", 8080)
+        .meta("version", "1")
+        .check(RegisterAgentCheck::http(
+            "http://10.0.0.1:8080/health",
+            "10s",
+        ))
+        .connect_native();
+    let json = serde_json::to_value(&reg).unwrap();
+    assert_eq!(json["TaggedAddresses"]["lan_ipv6"]["Address"], "::1");
+    assert_eq!(json["Checks"][0]["Interval"], "10s");
+    assert!(json["Checks"][0].get("TTL").is_none());
+    assert!(json["Checks"][0].get("Timeout").is_none());
+    assert_eq!(json["Connect"]["Native"], true);
+    assert!(json.get("Proxy").is_none());
+}
+
 #[tokio::test]
 async fn test_check() {
-    let config = Config::new().unwrap();
-    let client = Client::new(config);
+    let client = common::client().await;
     client
         .register_check(&RegisterAgentCheck {
             Name: "test name".to_string(),
@@ -43,7 +148,51 @@ async fn test_check() {
         })
         .await
         .unwrap();
-    let list = client.checks().await.unwrap();
+    let list = client.checks(Some(r#"CheckID == "test""#)).await.unwrap();
     assert!(list.contains_key("test"));
     client.deregister_check("test").await.unwrap();
 }
+
+#[tokio::test]
+async fn test_members() {
+    let client = common::client().await;
+    let members = client.members(false, None).await.unwrap();
+    assert!(!members.is_empty());
+    assert!(members.iter().any(|m| m.Status == MemberStatus::Alive));
+}
+
+#[tokio::test]
+async fn test_agent_self() {
+    let client = common::client().await;
+    let agent = client.agent_self().await.unwrap();
+    assert_eq!(agent.Config.Datacenter, "dc1");
+    assert_eq!(agent.Member.Name, agent.Config.NodeName);
+}
+
+#[test]
+fn test_diff_members() {
+    let member = |name: &str, status: MemberStatus| AgentMember {
+        Name: name.to_string(),
+        Status: status,
+        ..Default::default()
+    };
+    let old = vec![
+        member("a", MemberStatus::Alive),
+        member("b", MemberStatus::Alive),
+    ];
+    let new = vec![
+        member("b", MemberStatus::Failed),
+        member("c", MemberStatus::Alive),
+    ];
+    let diff = diff_members(&old, &new);
+    assert_eq!(diff.added, vec![member("c", MemberStatus::Alive)]);
+    assert_eq!(diff.removed, vec![member("a", MemberStatus::Alive)]);
+    assert_eq!(
+        diff.status_changed,
+        vec![(
+            member("b", MemberStatus::Alive),
+            member("b", MemberStatus::Failed)
+        )]
+    );
+    assert!(diff_members(&new, &new).is_empty());
+}
@@ -1,11 +1,14 @@
 extern crate consul;
+
+mod common;
+
 use consul::{Client, Config};
 
 #[tokio::test]
 async fn ds_test() {
     use consul::catalog::Catalog;
     let config = Config::new_from_env().unwrap();
-    let client = Client::new(config);
+    let client = common::ready(Client::new(config)).await;
     let r = client.datacenters().await.unwrap();
     assert_eq!(r.0, ["dc1"]);
 }
@@ -13,8 +16,7 @@ async fn ds_test() {
 #[tokio::test]
 async fn ds_services_test() {
     use consul::catalog::Catalog;
-    let config = Config::new().unwrap();
-    let client = Client::new(config);
+    let client = common::client().await;
     let r = client.services(Option::None).await.unwrap();
     assert_ne!(r.0.len(), 0);
     match r.0.get("consul") {
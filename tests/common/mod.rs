@@ -0,0 +1,91 @@
+#![allow(dead_code)]
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use consul::{Client, Config};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+/// Waits for the test agent, the tests would otherwise race its boot.
+pub async fn ready(client: Client) -> Client {
+    client
+        .wait_until_ready(Duration::from_secs(30))
+        .await
+        .unwrap();
+    client
+}
+
+/// A client of the local test agent, once it is ready.
+pub async fn client() -> Client {
+    ready(Client::new(Config::new().unwrap())).await
+}
+
+/// Reads the request line and headers of a request.
+pub async fn read_request(stream: &mut tokio::net::TcpStream) -> String {
+    let mut request = Vec::new();
+    let mut buf = [0; 1024];
+    while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+        let n = stream.read(&mut buf).await.unwrap();
+        request.extend_from_slice(&buf[..n]);
+    }
+    String::from_utf8(request).unwrap()
+}
+
+/// Answers a single request with `body`, and returns the request line and headers.
+pub async fn serve_once(listener: &tokio::net::TcpListener, body: &str) -> String {
+    serve_once_with_status(listener, "200 OK", body).await
+}
+
+/// Like `serve_once`, with another status than `200 OK`.
+pub async fn serve_once_with_status(
+    listener: &tokio::net::TcpListener,
+    status: &str,
+    body: &str,
+) -> String {
+    let (mut stream, _) = listener.accept().await.unwrap();
+    let request = read_request(&mut stream).await;
+    let response = format!(
+        "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+        status,
+        body.len(),
+        body
+    );
+    stream.write_all(response.as_bytes()).await.unwrap();
+    request
+}
+
+/// Answers every request with `body` and, if any, `index` as `X-Consul-Index`, without
+/// ever blocking. Returns the number of requests served so far.
+pub fn serve_all(
+    listener: tokio::net::TcpListener,
+    body: &str,
+    index: Option<u64>,
+) -> Arc<AtomicUsize> {
+    let served = Arc::new(AtomicUsize::new(0));
+    let body = body.to_owned();
+    let counter = served.clone();
+    tokio::spawn(async move {
+        loop {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let body = body.clone();
+            let counter = counter.clone();
+            tokio::spawn(async move {
+                read_request(&mut stream).await;
+                counter.fetch_add(1, Ordering::SeqCst);
+                let index = index
+                    .map(|index| format!("X-Consul-Index: {}\r\n", index))
+                    .unwrap_or_default();
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n{}\
+                     Connection: close\r\nContent-Length: {}\r\n\r\n{}",
+                    index,
+                    body.len(),
+                    body
+                );
+                stream.write_all(response.as_bytes()).await.unwrap();
+            });
+        }
+    });
+    served
+}
@@ -0,0 +1,139 @@
+mod common;
+
+use consul::config_entry::{
+    ConfigEntries, ConfigEntry, IntentionAction, IntentionHTTPPermission, IntentionPermission,
+    ServiceConfigEntry, ServiceIntentionsConfigEntry, SourceIntention, SERVICE_DEFAULTS,
+};
+
+#[test]
+fn config_entry_kind_test() {
+    let json = r#"{
+        "Kind": "service-resolver",
+        "Name": "web",
+        "DefaultSubset": "v1",
+        "Subsets": {"v1": {"Filter": "Service.Meta.version == v1"}},
+        "ConnectTimeout": "15s",
+        "CreateIndex": 10,
+        "ModifyIndex": 12
+    }"#;
+    let entry: ConfigEntry = serde_json::from_str(json).unwrap();
+    assert_eq!(entry.kind(), "service-resolver");
+    assert_eq!(entry.name(), "web");
+    assert_eq!(entry.modify_index(), 12);
+    match &entry {
+        ConfigEntry::ServiceResolver(resolver) => {
+            assert_eq!(resolver.DefaultSubset, "v1");
+            assert_eq!(resolver.ConnectTimeout, "15s");
+            assert!(resolver.Subsets.contains_key("v1"));
+        }
+        _ => panic!("unexpected entry {:?}", entry),
+    }
+
+    let mesh: ConfigEntry = serde_json::from_str(r#"{"Kind": "mesh"}"#).unwrap();
+    assert_eq!(mesh.name(), "mesh");
+    assert!(serde_json::from_str::<ConfigEntry>(r#"{"Kind": "unknown"}"#).is_err());
+}
+
+#[test]
+fn config_entry_round_trip_test() {
+    let json = r#"{
+        "Kind": "service-intentions",
+        "Name": "api",
+        "Sources": [
+            {"Name": "web", "Action": "allow", "Precedence": 9, "Type": "consul"},
+            {
+                "Name": "admin",
+                "Permissions": [{
+                    "Action": "allow",
+                    "HTTP": {
+                        "PathPrefix": "/admin",
+                        "Header": [{"Name": "X-Role", "Exact": "admin"}],
+                        "Methods": ["GET", "POST"]
+                    }
+                }]
+            }
+        ],
+        "FutureField": {"Nested": [1, 2]},
+        "CreateIndex": 3,
+        "ModifyIndex": 4
+    }"#;
+    let entry: ConfigEntry = serde_json::from_str(json).unwrap();
+    match &entry {
+        ConfigEntry::ServiceIntentions(intentions) => {
+            assert_eq!(intentions.Sources[0].Action, Some(IntentionAction::Allow));
+            let http = intentions.Sources[1].Permissions[0].HTTP.as_ref().unwrap();
+            assert_eq!(http.PathPrefix, "/admin");
+            assert_eq!(http.Header[0].Exact, "admin");
+            assert!(intentions.Extra.contains_key("FutureField"));
+        }
+        _ => panic!("unexpected entry {:?}", entry),
+    }
+
+    let encoded = serde_json::to_value(&entry).unwrap();
+    assert_eq!(encoded["Kind"], "service-intentions");
+    assert_eq!(encoded["FutureField"]["Nested"][1], 2);
+    let decoded: ConfigEntry = serde_json::from_value(encoded).unwrap();
+    assert_eq!(decoded, entry);
+}
+
+#[tokio::test]
+async fn config_entry_test() {
+    let client = common::client().await;
+    let entry = ConfigEntry::ServiceDefaults(ServiceConfigEntry {
+        Name: String::from("config-entry-test"),
+        Protocol: String::from("http"),
+        ..Default::default()
+    });
+    let (written, _) = client.config_set(&entry, Some(0), None).await.unwrap();
+    assert!(written);
+    let (written, _) = client.config_set(&entry, Some(0), None).await.unwrap();
+    assert!(!written);
+
+    let (fetched, _) = client
+        .config_get(SERVICE_DEFAULTS, "config-entry-test", None)
+        .await
+        .unwrap();
+    match &fetched {
+        ConfigEntry::ServiceDefaults(defaults) => assert_eq!(defaults.Protocol, "http"),
+        _ => panic!("unexpected entry {:?}", fetched),
+    }
+    let (entries, _) = client.config_list(SERVICE_DEFAULTS, None).await.unwrap();
+    assert!(entries.iter().any(|e| e.name() == "config-entry-test"));
+
+    let intentions = ConfigEntry::ServiceIntentions(ServiceIntentionsConfigEntry {
+        Name: String::from("config-entry-test"),
+        Sources: vec![SourceIntention {
+            Name: String::from("web"),
+            Permissions: vec![IntentionPermission {
+                Action: IntentionAction::Allow,
+                HTTP: Some(IntentionHTTPPermission {
+                    PathPrefix: String::from("/v1"),
+                    ..Default::default()
+                }),
+            }],
+            ..Default::default()
+        }],
+        ..Default::default()
+    });
+    assert!(client.config_set(&intentions, None, None).await.unwrap().0);
+    client
+        .config_delete("service-intentions", "config-entry-test", None)
+        .await
+        .unwrap();
+
+    let (deleted, _) = client
+        .config_delete_cas(SERVICE_DEFAULTS, "config-entry-test", 1, None)
+        .await
+        .unwrap();
+    assert!(!deleted);
+    let (deleted, _) = client
+        .config_delete_cas(
+            SERVICE_DEFAULTS,
+            "config-entry-test",
+            fetched.modify_index(),
+            None,
+        )
+        .await
+        .unwrap();
+    assert!(deleted);
+}
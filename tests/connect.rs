@@ -0,0 +1,313 @@
+#![cfg(feature = "connect")]
+
+mod common;
+
+use std::time::Duration;
+
+use consul::connect::leaf::{ConnectTls, LeafCertManager};
+use consul::connect_ca::{CARootList, LeafCert};
+use rcgen::{BasicConstraints, CertificateParams, Ia5String, IsCa, KeyPair, SanType};
+use std::convert::TryFrom;
+
+const TRUST_DOMAIN: &str = "11111111-2222-3333-4444-555555555555.consul";
+
+struct TestCA {
+    cert: rcgen::Certificate,
+    key: KeyPair,
+}
+
+impl TestCA {
+    fn new() -> TestCA {
+        let key = KeyPair::generate().unwrap();
+        let mut params = CertificateParams::new(Vec::new()).unwrap();
+        params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+        let cert = params.self_signed(&key).unwrap();
+        TestCA { cert, key }
+    }
+
+    /// The CA roots, as Consul would return them.
+    fn roots(&self) -> CARootList {
+        serde_json::from_value(serde_json::json!({
+            "ActiveRootID": "root",
+            "TrustDomain": TRUST_DOMAIN,
+            "Roots": [{"ID": "root", "RootCert": self.cert.pem(), "Active": true}],
+        }))
+        .unwrap()
+    }
+
+    /// A leaf certificate for `service`, valid for `lifetime` from `not_before`.
+    fn issue(&self, service: &str, not_before: (i32, u8, u8), lifetime: Duration) -> LeafCert {
+        self.issue_in("default", service, not_before, lifetime)
+    }
+
+    /// Like `issue`, for a service of `namespace`.
+    fn issue_in(
+        &self,
+        namespace: &str,
+        service: &str,
+        not_before: (i32, u8, u8),
+        lifetime: Duration,
+    ) -> LeafCert {
+        let uri = format!(
+            "spiffe://{}/ns/{}/dc/dc1/svc/{}",
+            TRUST_DOMAIN, namespace, service
+        );
+        let key = KeyPair::generate().unwrap();
+        let mut params = CertificateParams::new(Vec::new()).unwrap();
+        params.subject_alt_names = vec![SanType::URI(Ia5String::try_from(uri.clone()).unwrap())];
+        let (year, month, day) = not_before;
+        params.not_before = rcgen::date_time_ymd(year, month, day);
+        params.not_after = params.not_before + lifetime;
+        let cert = params.signed_by(&key, &self.cert, &self.key).unwrap();
+        LeafCert {
+            CertPEM: cert.pem(),
+            PrivateKeyPEM: key.serialize_pem(),
+            Service: service.to_owned(),
+            ServiceURI: uri,
+            ..Default::default()
+        }
+    }
+}
+
+/// Answers the leaf certificate and CA roots requests like an agent would, the
+/// blocking queries never return.
+async fn serve_ca(listener: tokio::net::TcpListener, leaf: LeafCert, roots: CARootList) {
+    use tokio::io::AsyncWriteExt;
+
+    loop {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        let leaf = serde_json::to_string(&leaf).unwrap();
+        let roots = serde_json::to_string(&roots).unwrap();
+        tokio::spawn(async move {
+            let request = common::read_request(&mut stream).await;
+            let line = request.lines().next().unwrap();
+            if line.contains("index=") {
+                return std::future::pending().await;
+            }
+            let body = if line.contains("/v1/connect/ca/roots") {
+                roots
+            } else {
+                leaf
+            };
+            let response = format!(
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nX-Consul-Index: 1\r\n\
+                 Connection: close\r\nContent-Length: {}\r\n\r\n{}",
+                body.len(),
+                body
+            );
+            stream.write_all(response.as_bytes()).await.unwrap();
+        });
+    }
+}
+
+#[test]
+fn connect_tls_test() {
+    let ca = TestCA::new();
+    let leaf = ca.issue("web", (2020, 1, 1), Duration::from_secs(10 * 24 * 3600));
+    let tls = ConnectTls::new(leaf.clone(), ca.roots()).unwrap();
+    let lifetime = tls.valid_before.duration_since(tls.valid_after).unwrap();
+    assert_eq!(lifetime, Duration::from_secs(10 * 24 * 3600));
+    assert_eq!(
+        tls.valid_before.duration_since(tls.renew_at()).unwrap(),
+        Duration::from_secs(24 * 3600)
+    );
+
+    let no_key = LeafCert {
+        PrivateKeyPEM: String::new(),
+        ..leaf
+    };
+    assert!(ConnectTls::new(no_key, ca.roots()).is_err());
+}
+
+#[test]
+fn serial_number_test() {
+    use consul::connect::native::serial_number;
+
+    let ca = TestCA::new();
+    let issue = |serial: &[u8]| {
+        let key = KeyPair::generate().unwrap();
+        let mut params = CertificateParams::new(Vec::new()).unwrap();
+        params.serial_number = Some(rcgen::SerialNumber::from_slice(serial));
+        params.signed_by(&key, &ca.cert, &ca.key).unwrap()
+    };
+    // DER prefixes the serial with a zero byte as its high bit is set
+    let cert = issue(&[0x80, 0x01, 0xab]);
+    assert_eq!(serial_number(cert.der()).unwrap(), "80:01:ab");
+    let cert = issue(&[0x0c, 0xff]);
+    assert_eq!(serial_number(cert.der()).unwrap(), "0c:ff");
+}
+
+#[test]
+fn connect_tls_before_epoch_test() {
+    let ca = TestCA::new();
+    let leaf = ca.issue(
+        "web",
+        (1960, 1, 1),
+        Duration::from_secs(100 * 365 * 24 * 3600),
+    );
+    match ConnectTls::new(leaf, ca.roots()) {
+        Err(consul::errors::Error::InvalidCertificate(_)) => {}
+        Err(e) => panic!("Expected InvalidCertificate, got {:?}", e),
+        Ok(_) => panic!("Expected InvalidCertificate"),
+    }
+}
+
+#[tokio::test]
+async fn connect_tls_handshake_test() {
+    use rustls::pki_types::ServerName;
+    use tokio_rustls::{TlsAcceptor, TlsConnector};
+
+    let ca = TestCA::new();
+    let issue = |ca: &TestCA, service| {
+        let leaf = ca.issue(
+            service,
+            (2020, 1, 1),
+            Duration::from_secs(100 * 365 * 24 * 3600),
+        );
+        ConnectTls::new(leaf, ca.roots()).unwrap()
+    };
+    let server = issue(&ca, "api");
+    let client = issue(&ca, "web");
+    let stranger = issue(&TestCA::new(), "web");
+
+    let name = ServerName::try_from("api").unwrap();
+    let (client_io, server_io) = tokio::io::duplex(16 * 1024);
+    let acceptor = TlsAcceptor::from(server.server_config.clone());
+    let accepted = tokio::spawn(async move { acceptor.accept(server_io).await });
+    let connector = TlsConnector::from(client.client_config.clone());
+    let _client_stream = connector.connect(name.clone(), client_io).await.unwrap();
+    let stream = accepted.await.unwrap().unwrap();
+    assert_eq!(stream.get_ref().1.peer_certificates().unwrap().len(), 1);
+
+    let (client_io, server_io) = tokio::io::duplex(16 * 1024);
+    let acceptor = TlsAcceptor::from(server.server_config.clone());
+    let accepted = tokio::spawn(async move { acceptor.accept(server_io).await });
+    let connector = TlsConnector::from(stranger.client_config.clone());
+    assert!(connector.connect(name, client_io).await.is_err());
+    assert!(accepted.await.unwrap().is_err());
+}
+
+#[tokio::test]
+async fn leaf_cert_manager_test() {
+    let client = common::client().await;
+    let manager = LeafCertManager::new(client, "leaf-cert-test")
+        .await
+        .unwrap();
+    let current = manager.current();
+    assert_eq!(current.leaf.Service, "leaf-cert-test");
+    assert!(current.leaf.ServiceURI.starts_with("spiffe://"));
+    assert!(current.valid_before > std::time::SystemTime::now());
+    assert!(std::sync::Arc::ptr_eq(
+        &manager.server_config(),
+        &current.server_config
+    ));
+}
+
+#[tokio::test]
+async fn connect_native_test() {
+    use consul::agent::{Agent, RegisterAgentService};
+    use consul::connect::native::{ConnectAcceptor, ConnectConnector};
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    let client = common::client().await;
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+    let service = RegisterAgentService::new("connect-native-test")
+        .address("127.0.0.1")
+        .port(port)
+        .connect_native();
+    client.register_service(&service, false).await.unwrap();
+
+    let server_certs = LeafCertManager::new(client.clone(), "connect-native-test")
+        .await
+        .unwrap();
+    let client_certs = LeafCertManager::new(client.clone(), "connect-native-client")
+        .await
+        .unwrap();
+    let acceptor = ConnectAcceptor::new(client.clone(), &server_certs);
+    let connector = ConnectConnector::new(client.clone(), &client_certs);
+
+    let server = tokio::spawn(async move {
+        let (stream, _) = listener.accept().await.unwrap();
+        let (mut stream, peer) = acceptor.accept(stream).await.unwrap();
+        stream.write_all(b"pong").await.unwrap();
+        stream.shutdown().await.unwrap();
+        peer
+    });
+    let mut stream = connector.connect("connect-native-test").await.unwrap();
+    let mut reply = String::new();
+    stream.read_to_string(&mut reply).await.unwrap();
+    assert_eq!(reply, "pong");
+    let peer = server.await.unwrap();
+    assert_eq!(peer.service_name(), Some("connect-native-client"));
+
+    client
+        .deregister_service("connect-native-test")
+        .await
+        .unwrap();
+}
+
+#[test]
+fn spiffe_id_from_certificate_test() {
+    use consul::spiffe::SpiffeId;
+
+    let ca = TestCA::new();
+    let leaf = ca.issue("web", (2020, 1, 1), Duration::from_secs(3600));
+    let der = rustls_pemfile::certs(&mut leaf.CertPEM.as_bytes())
+        .next()
+        .unwrap()
+        .unwrap();
+    let id = SpiffeId::from_certificate(&der).unwrap();
+    assert_eq!(id.to_string(), leaf.ServiceURI);
+    assert!(id.validate(&ca.roots()).is_ok());
+
+    let ca_der = rustls_pemfile::certs(&mut ca.cert.pem().as_bytes())
+        .next()
+        .unwrap()
+        .unwrap();
+    assert!(SpiffeId::from_certificate(&ca_der).is_err());
+}
+
+#[tokio::test]
+async fn connect_to_namespace_test() {
+    use consul::connect::native::ConnectConnector;
+    use consul::errors::Error;
+    use consul::{Client, Config};
+    use tokio::net::TcpListener;
+    use tokio_rustls::TlsAcceptor;
+
+    let lifetime = Duration::from_secs(100 * 365 * 24 * 3600);
+    let ca = TestCA::new();
+    let agent = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = format!("http://{}", agent.local_addr().unwrap());
+    tokio::spawn(serve_ca(
+        agent,
+        ca.issue("web", (2020, 1, 1), lifetime),
+        ca.roots(),
+    ));
+    let client = Client::new(Config::new_from_addr(&address, None).unwrap());
+    let certs = LeafCertManager::new(client.clone(), "web").await.unwrap();
+    let connector = ConnectConnector::new(client, &certs);
+
+    // Same service name, but only the one of the client namespace is accepted
+    for (namespace, trusted) in [("default", true), ("other", false)] {
+        let leaf = ca.issue_in(namespace, "api", (2020, 1, 1), lifetime);
+        let server = ConnectTls::new(leaf, ca.roots()).unwrap();
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let acceptor = TlsAcceptor::from(server.server_config.clone());
+        let accepted = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            acceptor.accept(stream).await
+        });
+        let connected = connector.connect_to("api", addr).await;
+        let _ = accepted.await.unwrap();
+        match connected {
+            Ok(_) => assert!(trusted, "api in {} must be rejected", namespace),
+            Err(Error::InvalidSpiffeId(_)) => {
+                assert!(!trusted, "api in {} must be accepted", namespace)
+            }
+            Err(e) => panic!("Unexpected error {:?}", e),
+        }
+    }
+}
@@ -0,0 +1,117 @@
+mod common;
+
+use std::time::Duration;
+
+use consul::connect_ca::{CAConfig, CAProviderConfig, ConnectCA, ConsulCAProviderConfig};
+
+#[test]
+fn ca_config_serialization_test() {
+    let json = serde_json::json!({
+        "Provider": "vault",
+        "Config": {
+            "Address": "https://vault:8200",
+            "Token": "token",
+            "RootPKIPath": "connect-root",
+            "IntermediatePKIPath": "connect-intermediate",
+            "LeafCertTTL": "72h",
+            "FutureOption": 1
+        },
+        "CreateIndex": 5,
+        "ModifyIndex": 6
+    });
+    let config: CAConfig = serde_json::from_value(json.clone()).unwrap();
+    assert_eq!(config.provider(), "vault");
+    match &config.Config {
+        CAProviderConfig::Vault(vault) => {
+            assert_eq!(vault.RootPKIPath, "connect-root");
+            assert_eq!(vault.LeafCertTTL, "72h");
+        }
+        other => panic!("unexpected provider config {:?}", other),
+    }
+    assert_eq!(
+        serde_json::to_value(&config).unwrap()["Config"],
+        json["Config"]
+    );
+    assert_eq!(config.ModifyIndex, 6);
+
+    let json = serde_json::json!({
+        "Provider": "custom",
+        "Config": {"Endpoint": "ca.internal"},
+        "ForceWithoutCrossSigning": true
+    });
+    let config: CAConfig = serde_json::from_value(json.clone()).unwrap();
+    assert_eq!(config.provider(), "custom");
+    assert!(matches!(config.Config, CAProviderConfig::Raw { .. }));
+    let encoded = serde_json::to_value(&config).unwrap();
+    assert_eq!(encoded["Provider"], "custom");
+    assert_eq!(encoded["Config"], json["Config"]);
+    assert_eq!(encoded["ForceWithoutCrossSigning"], true);
+
+    let config = CAConfig::new(CAProviderConfig::Consul(ConsulCAProviderConfig {
+        PrivateKeyType: String::from("rsa"),
+        PrivateKeyBits: Some(2048),
+        ..Default::default()
+    }));
+    let encoded = serde_json::to_value(&config).unwrap();
+    assert_eq!(encoded["Provider"], "consul");
+    assert_eq!(
+        encoded["Config"],
+        serde_json::json!({"PrivateKeyType": "rsa", "PrivateKeyBits": 2048})
+    );
+}
+
+#[tokio::test]
+async fn ca_rotation_test() {
+    let client = common::client().await;
+    let (roots, _) = client.ca_roots(None).await.unwrap();
+    let old_root = roots.active_root().unwrap().clone();
+    assert_eq!(old_root.ID, roots.ActiveRootID);
+
+    let (mut config, _) = client.ca_get_config(None).await.unwrap();
+    assert_eq!(config.provider(), "consul");
+    let key_type = if old_root.PrivateKeyType == "rsa" {
+        "ec"
+    } else {
+        "rsa"
+    };
+    match &mut config.Config {
+        CAProviderConfig::Consul(consul) => {
+            consul.PrivateKey = String::new();
+            consul.RootCert = String::new();
+            consul.PrivateKeyType = key_type.to_owned();
+            consul.PrivateKeyBits = Some(if key_type == "rsa" { 2048 } else { 256 });
+        }
+        other => panic!("unexpected provider config {:?}", other),
+    }
+
+    let roots = client
+        .ca_rotate(&config, Duration::from_secs(30))
+        .await
+        .unwrap();
+    let new_root = roots.active_root().unwrap();
+    assert_ne!(new_root.ID, old_root.ID);
+    assert_eq!(new_root.PrivateKeyType, key_type);
+    assert!(!new_root.IntermediateCerts.is_empty());
+    if let Some(retired) = roots.Roots.iter().find(|root| root.ID == old_root.ID) {
+        assert!(!retired.Active);
+        assert!(retired.RotatedOutAt.is_some());
+    }
+}
+
+#[tokio::test]
+async fn ca_rotation_timeout_test() {
+    use consul::errors::Error;
+    use consul::{Client, Config};
+
+    // Accepts connections but never answers
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = format!("http://{}", listener.local_addr().unwrap());
+    let client = Client::new(Config::new_from_addr(&address, None).unwrap());
+    let start = std::time::Instant::now();
+    let rotated = client
+        .ca_rotate(&CAConfig::default(), Duration::from_millis(300))
+        .await;
+    assert!(matches!(rotated, Err(Error::CARotationIncomplete(_))));
+    assert!(start.elapsed() < Duration::from_secs(2));
+    drop(listener);
+}
@@ -0,0 +1,79 @@
+mod common;
+
+use std::time::Duration;
+
+use consul::coordinate::{distance_rtt, rank_by_rtt, Coordinate, CoordinateEntry, Coordinates};
+use consul::health::{Node, ServiceEntry};
+
+fn coordinate(vec: Vec<f64>, height: f64, adjustment: f64) -> Coordinate {
+    Coordinate {
+        Vec: vec,
+        Error: 1.5,
+        Adjustment: adjustment,
+        Height: height,
+    }
+}
+
+#[test]
+fn distance_rtt_test() {
+    let a = coordinate(vec![0.0, 0.0, 0.0], 0.0, 0.0);
+    let b = coordinate(vec![0.003, 0.004, 0.0], 0.0, 0.0);
+    assert_eq!(distance_rtt(&a, &b), Some(Duration::from_millis(5)));
+
+    // Heights are added, adjustments are used only when the result stays positive
+    let c = coordinate(vec![0.003, 0.004, 0.0], 0.001, 0.002);
+    assert_eq!(distance_rtt(&a, &c), Some(Duration::from_millis(8)));
+    let d = coordinate(vec![0.003, 0.004, 0.0], 0.001, -0.01);
+    assert_eq!(distance_rtt(&a, &d), Some(Duration::from_millis(6)));
+
+    assert_eq!(distance_rtt(&a, &coordinate(vec![0.0], 0.0, 0.0)), None);
+}
+
+#[test]
+fn rank_by_rtt_test() {
+    let entry = |node: &str| ServiceEntry {
+        Node: Node {
+            Node: node.to_string(),
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let entries = vec![entry("far"), entry("unknown"), entry("near")];
+    let coordinates = vec![
+        CoordinateEntry {
+            Node: String::from("far"),
+            Coord: coordinate(vec![0.1, 0.0], 0.0, 0.0),
+            ..Default::default()
+        },
+        CoordinateEntry {
+            Node: String::from("near"),
+            Coord: coordinate(vec![0.01, 0.0], 0.0, 0.0),
+            ..Default::default()
+        },
+    ];
+    let origin = coordinate(vec![0.0, 0.0], 0.0, 0.0);
+    let ranked: Vec<_> = rank_by_rtt(&origin, &entries, &coordinates)
+        .into_iter()
+        .map(|(e, rtt)| (e.Node.Node.as_str(), rtt))
+        .collect();
+    assert_eq!(
+        ranked,
+        [
+            ("near", Some(Duration::from_millis(10))),
+            ("far", Some(Duration::from_millis(100))),
+            ("unknown", None),
+        ]
+    );
+}
+
+#[tokio::test]
+async fn coordinate_test() {
+    let client = common::client().await;
+    let (datacenters, _) = client.coordinate_datacenters(None).await.unwrap();
+    assert_eq!(datacenters[0].Datacenter, "dc1");
+    let (nodes, _) = client.coordinate_nodes(None, None).await.unwrap();
+    if let Some(entry) = nodes.first() {
+        let (node, _) = client.coordinate_node(&entry.Node, None).await.unwrap();
+        assert_eq!(node[0].Node, entry.Node);
+    }
+}
@@ -0,0 +1,152 @@
+mod common;
+
+use std::net::SocketAddr;
+
+use consul::agent::{AgentService, AgentWeights};
+use consul::discovery::{
+    Instance, LeastRecentlyUsed, RoundRobin, Selector, ServiceDiscovery, Weighted,
+};
+use consul::health::{HealthCheck, Node, ServiceEntry};
+
+fn instance(service_id: &str, passing: i32, warning: bool) -> Instance {
+    Instance {
+        node: String::from("node"),
+        service_id: service_id.to_string(),
+        address: "127.0.0.1:8080".parse().unwrap(),
+        tags: Vec::new(),
+        meta: Default::default(),
+        weights: AgentWeights {
+            Passing: passing,
+            Warning: 0,
+        },
+        warning,
+    }
+}
+
+#[test]
+fn instance_from_entry_test() {
+    let mut entry = ServiceEntry {
+        Node: Node {
+            Node: String::from("node"),
+            Address: String::from("10.0.0.1"),
+            ..Default::default()
+        },
+        Service: AgentService {
+            ID: String::from("web-1"),
+            Port: 8080,
+            ..Default::default()
+        },
+        Checks: vec![HealthCheck {
+            Status: String::from("passing"),
+            ..Default::default()
+        }],
+    };
+    let from_node = Instance::from_entry(&entry).unwrap();
+    assert_eq!(
+        from_node.address,
+        "10.0.0.1:8080".parse::<SocketAddr>().unwrap()
+    );
+    assert_eq!(from_node.weight(), 1);
+
+    entry.Service.Address = String::from("10.0.0.2");
+    let from_service = Instance::from_entry(&entry).unwrap();
+    assert_eq!(
+        from_service.address,
+        "10.0.0.2:8080".parse::<SocketAddr>().unwrap()
+    );
+
+    entry.Checks[0].Status = String::from("critical");
+    assert!(Instance::from_entry(&entry).is_none());
+}
+
+#[test]
+fn round_robin_test() {
+    let instances = vec![instance("a", 1, false), instance("b", 1, false)];
+    let selector = RoundRobin::default();
+    let picked: Vec<_> = (0..4)
+        .map(|_| selector.select(&instances).unwrap().service_id.clone())
+        .collect();
+    assert_eq!(picked, ["a", "b", "a", "b"]);
+    assert!(selector.select(&[]).is_none());
+}
+
+#[test]
+fn weighted_test() {
+    let instances = vec![
+        instance("a", 0, false),
+        instance("b", 5, true),
+        instance("c", 3, false),
+    ];
+    for _ in 0..20 {
+        assert_eq!(Weighted.select(&instances).unwrap().service_id, "c");
+    }
+    assert!(Weighted.select(&instances[..2]).is_none());
+}
+
+#[test]
+fn least_recently_used_test() {
+    let mut instances = vec![instance("a", 1, false), instance("b", 1, false)];
+    let selector = LeastRecentlyUsed::default();
+    assert_eq!(selector.select(&instances).unwrap().service_id, "a");
+    assert_eq!(selector.select(&instances).unwrap().service_id, "b");
+    instances.push(instance("c", 1, false));
+    assert_eq!(selector.select(&instances).unwrap().service_id, "c");
+    assert_eq!(selector.select(&instances).unwrap().service_id, "a");
+}
+
+#[tokio::test]
+async fn service_discovery_test() {
+    let client = common::client().await;
+    let discovery = ServiceDiscovery::builder(client, "consul")
+        .passing_only(true)
+        .build()
+        .await
+        .unwrap();
+    assert!(!discovery.instances().is_empty());
+    assert!(discovery.select().is_some());
+}
+
+#[tokio::test]
+async fn service_discovery_missing_index_test() {
+    use consul::{Client, Config};
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = format!("http://{}", listener.local_addr().unwrap());
+    let served = common::serve_all(listener, "[]", None);
+    let client = Client::new(Config::new_from_addr(&address, None).unwrap());
+
+    let discovery = ServiceDiscovery::builder(client, "web")
+        .build()
+        .await
+        .unwrap();
+    assert!(discovery.instances().is_empty());
+    // Without an `X-Consul-Index` the watcher waits between queries instead of spinning
+    tokio::time::sleep(std::time::Duration::from_millis(1500)).await;
+    assert!(served.load(std::sync::atomic::Ordering::SeqCst) <= 3);
+}
+
+#[cfg(feature = "tower")]
+#[tokio::test]
+async fn service_discovery_tower_test() {
+    use std::pin::Pin;
+    use tower::discover::{Change, Discover};
+
+    let client = common::client().await;
+    let discovery = ServiceDiscovery::builder(client, "consul")
+        .build()
+        .await
+        .unwrap();
+    let mut discover = discovery.discover(|instance| instance.address);
+    let change = std::future::poll_fn(|cx| Pin::new(&mut discover).poll_discover(cx))
+        .await
+        .unwrap()
+        .unwrap();
+    match change {
+        Change::Insert(key, address) => {
+            let instance = discovery.instances()[0].clone();
+            assert_eq!(key, instance.key());
+            assert_eq!(address, instance.address);
+        }
+        Change::Remove(_) => panic!("First change must be an insertion"),
+    }
+}
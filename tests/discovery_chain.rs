@@ -0,0 +1,95 @@
+mod common;
+
+use consul::discovery_chain::{
+    CompiledDiscoveryChain, DiscoveryChain, DiscoveryChainOptions, DiscoveryGraphNodeType,
+};
+
+#[test]
+fn discovery_chain_serialization_test() {
+    let json = r#"{
+        "ServiceName": "web",
+        "Namespace": "default",
+        "Datacenter": "dc1",
+        "Protocol": "http",
+        "StartNode": "router:web.default",
+        "Nodes": {
+            "router:web.default": {
+                "Type": "router",
+                "Name": "web.default",
+                "Routes": [{
+                    "Definition": {
+                        "Match": {"HTTP": {"PathPrefix": "/admin"}},
+                        "Destination": {"Service": "admin"}
+                    },
+                    "NextNode": "resolver:admin.default.default.dc1"
+                }]
+            },
+            "resolver:admin.default.default.dc1": {
+                "Type": "resolver",
+                "Name": "admin.default.default.dc1",
+                "Resolver": {
+                    "ConnectTimeout": "5s",
+                    "Target": "admin.default.default.dc1",
+                    "Failover": {"Targets": ["admin.default.default.dc2"]}
+                }
+            }
+        },
+        "Targets": {
+            "admin.default.default.dc1": {
+                "ID": "admin.default.default.dc1",
+                "Service": "admin",
+                "Datacenter": "dc1",
+                "MeshGateway": {},
+                "Subset": {},
+                "ConnectTimeout": "5s",
+                "SNI": "admin.default.dc1.internal.example.consul"
+            }
+        }
+    }"#;
+    let chain: CompiledDiscoveryChain = serde_json::from_str(json).unwrap();
+    let start = chain.start_node().unwrap();
+    assert_eq!(start.Type, DiscoveryGraphNodeType::Router);
+    let route = &start.Routes[0];
+    let route_match = route.Definition.as_ref().unwrap().Match.as_ref().unwrap();
+    assert_eq!(route_match.HTTP.as_ref().unwrap().PathPrefix, "/admin");
+
+    let resolver = chain.node(&route.NextNode).unwrap();
+    assert_eq!(resolver.Type, DiscoveryGraphNodeType::Resolver);
+    let resolver = resolver.Resolver.as_ref().unwrap();
+    assert_eq!(resolver.Failover.as_ref().unwrap().Targets.len(), 1);
+    let target = chain.target(&resolver.Target).unwrap();
+    assert_eq!(target.Service, "admin");
+    assert_eq!(target.ConnectTimeout, "5s");
+}
+
+#[tokio::test]
+async fn discovery_chain_test() {
+    let client = common::client().await;
+    let (chain, meta) = client
+        .discovery_chain("discovery-chain-test", None, None)
+        .await
+        .unwrap();
+    assert!(chain.Default);
+    assert_eq!(chain.ServiceName, "discovery-chain-test");
+    assert_eq!(chain.Protocol, "tcp");
+    let start = chain.start_node().unwrap();
+    assert_eq!(start.Type, DiscoveryGraphNodeType::Resolver);
+    let target = start.Resolver.as_ref().unwrap().Target.clone();
+    assert_eq!(
+        chain.target(&target).unwrap().Service,
+        "discovery-chain-test"
+    );
+    assert!(meta.last_index.is_some());
+
+    let options = DiscoveryChainOptions {
+        OverrideProtocol: String::from("http"),
+        OverrideConnectTimeout: String::from("7s"),
+        ..Default::default()
+    };
+    let (chain, _) = client
+        .discovery_chain("discovery-chain-test", Some(&options), None)
+        .await
+        .unwrap();
+    assert_eq!(chain.Protocol, "http");
+    assert!(!chain.CustomizationHash.is_empty());
+}
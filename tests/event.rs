@@ -0,0 +1,71 @@
+mod common;
+
+use std::time::Duration;
+
+use base64::Engine;
+use consul::event::{idx_from_uuid, Event, EventFilter, EventWatch};
+
+#[test]
+fn idx_from_uuid_test() {
+    assert_eq!(
+        idx_from_uuid("00000000-0000-0001-0000-000000000003"),
+        Some(0x1 ^ 0x3)
+    );
+    assert_eq!(
+        idx_from_uuid("12345678-9abc-def0-1234-56789abcdef0"),
+        Some(0x1234_5678_9abc_def0 ^ 0x1234_5678_9abc_def0)
+    );
+    assert_eq!(idx_from_uuid("not-a-uuid"), None);
+    assert_eq!(idx_from_uuid("zzzzzzzz-0000-0001-0000-000000000003"), None);
+}
+
+#[tokio::test]
+async fn event_fire_and_list_test() {
+    let client = common::client().await;
+    let (fired, _) = client
+        .event_fire(
+            "event-list-test",
+            Some(b"flush"),
+            &EventFilter::default(),
+            None,
+        )
+        .await
+        .unwrap();
+    assert_eq!(fired.Name, "event-list-test");
+
+    let (events, meta) = client
+        .event_list(Some("event-list-test"), None)
+        .await
+        .unwrap();
+    let event = events.iter().find(|e| e.ID == fired.ID).unwrap();
+    let payload = base64::prelude::BASE64_STANDARD
+        .decode(event.Payload.as_ref().unwrap())
+        .unwrap();
+    assert_eq!(payload, b"flush");
+    assert_eq!(meta.last_index, idx_from_uuid(&events.last().unwrap().ID));
+}
+
+#[tokio::test]
+async fn event_watch_test() {
+    let client = common::client().await;
+    let mut watch = EventWatch::new(client.clone(), Some("event-watch-test"));
+    let waiter = tokio::spawn(async move {
+        let first = watch.next().await.unwrap();
+        let second = watch.next().await.unwrap();
+        (first, second)
+    });
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    let mut fired = Vec::new();
+    for _ in 0..2 {
+        let (event, _) = client
+            .event_fire("event-watch-test", None, &EventFilter::default(), None)
+            .await
+            .unwrap();
+        fired.push(event.ID);
+    }
+    let (first, second) = tokio::time::timeout(Duration::from_secs(5), waiter)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(vec![first.ID, second.ID], fired);
+}
@@ -0,0 +1,117 @@
+mod common;
+
+use consul::config_entry::{
+    ConfigEntries, ConfigEntry, ExportedService, ExportedServicesConfigEntry, ServiceConsumer,
+    EXPORTED_SERVICES,
+};
+use consul::exported_services::{ExportedServices, ResolvedExportedService};
+use consul::{Client, Config, QueryOptions};
+
+#[test]
+fn exported_services_serialization_test() {
+    let json = r#"[{
+        "Service": "web",
+        "Consumers": {"Peers": ["cluster-02"], "Partitions": ["team"]}
+    }]"#;
+    let exported: Vec<ResolvedExportedService> = serde_json::from_str(json).unwrap();
+    assert!(exported[0].is_exported_to_peer("cluster-02"));
+    assert!(!exported[0].is_exported_to_peer("cluster-03"));
+    assert_eq!(exported[0].Consumers.Partitions, vec!["team"]);
+    assert!(exported[0].Consumers.SamenessGroups.is_empty());
+}
+
+#[tokio::test]
+async fn exported_services_test() {
+    let client = common::client().await;
+    let entry = ConfigEntry::ExportedServices(ExportedServicesConfigEntry {
+        Name: String::from("default"),
+        Services: vec![ExportedService {
+            Name: String::from("exported-services-test"),
+            Consumers: vec![ServiceConsumer {
+                Peer: String::from("exported-services-peer"),
+                ..Default::default()
+            }],
+            ..Default::default()
+        }],
+        ..Default::default()
+    });
+    client.config_set(&entry, None, None).await.unwrap();
+
+    let (exported, _) = client.exported_services(None).await.unwrap();
+    let service = exported
+        .iter()
+        .find(|s| s.Service == "exported-services-test")
+        .unwrap();
+    assert_eq!(service.Consumers.Peers, vec!["exported-services-peer"]);
+    assert!(client
+        .is_reachable_from_peer("exported-services-test", "exported-services-peer", None)
+        .await
+        .unwrap());
+    assert!(!client
+        .is_reachable_from_peer("exported-services-test", "other-peer", None)
+        .await
+        .unwrap());
+
+    let (imported, _) = client.imported_services(None).await.unwrap();
+    assert!(imported.is_empty());
+
+    client
+        .config_delete(EXPORTED_SERVICES, "default", None)
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn reachable_from_peer_namespace_test() {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = format!("http://{}", listener.local_addr().unwrap());
+    common::serve_all(
+        listener,
+        r#"[
+            {"Service": "web", "Namespace": "a", "Consumers": {"Peers": ["cluster-02"]}},
+            {"Service": "web", "Namespace": "b", "Consumers": {"Partitions": ["team"]}},
+            {"Service": "api", "Consumers": {"Peers": ["cluster-02"]}}
+        ]"#,
+        Some(1),
+    );
+    let mut config = Config::new_from_addr(&address, None).unwrap();
+    config.namespace = Some(String::from("b"));
+    let client = Client::new(config);
+
+    let reachable = |service: &'static str, namespace: Option<&str>| {
+        let client = client.clone();
+        let q = QueryOptions {
+            namespace: namespace.map(String::from),
+            ..Default::default()
+        };
+        async move {
+            client
+                .is_reachable_from_peer(service, "cluster-02", Some(&q))
+                .await
+                .unwrap()
+        }
+    };
+    assert!(!reachable("web", None).await);
+    assert!(reachable("web", Some("a")).await);
+    assert!(!reachable("api", None).await);
+    assert!(reachable("api", Some("default")).await);
+}
+
+#[tokio::test]
+async fn reachable_from_peer_sameness_group_test() {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = format!("http://{}", listener.local_addr().unwrap());
+    common::serve_all(
+        listener,
+        r#"[{"Service": "web", "Consumers": {"SamenessGroups": ["group"]}}]"#,
+        Some(1),
+    );
+    let client = Client::new(Config::new_from_addr(&address, None).unwrap());
+
+    // Sameness groups aren't resolved, even when `cluster-02` is a member of `group`
+    let reachable = client
+        .is_reachable_from_peer("web", "cluster-02", None)
+        .await
+        .unwrap();
+    assert!(!reachable);
+}
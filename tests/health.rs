@@ -1,11 +1,11 @@
 extern crate consul;
-use consul::{Client, Config};
+
+mod common;
 
 #[tokio::test]
 async fn health_test() {
     use consul::health::Health;
-    let config = Config::new().unwrap();
-    let client = Client::new(config);
+    let client = common::client().await;
     // An existing service for a agent in dev mode
     let r = client.service("consul", None, true, None).await.unwrap();
     let (snodes, meta) = (r.0, r.1);
@@ -0,0 +1,85 @@
+mod common;
+
+use consul::config_entry::{
+    HTTPHeaderMatch, IntentionAction, IntentionHTTPPermission, IntentionPermission,
+};
+use consul::intention::{Intention, IntentionMatchType, Intentions};
+
+#[test]
+fn intention_serialization_test() {
+    let intention = Intention {
+        SourceName: String::from("web"),
+        DestinationName: String::from("api"),
+        Permissions: vec![IntentionPermission {
+            Action: IntentionAction::Deny,
+            HTTP: Some(IntentionHTTPPermission {
+                PathExact: String::from("/admin"),
+                Header: vec![HTTPHeaderMatch {
+                    Name: String::from("X-Debug"),
+                    Present: true,
+                    ..Default::default()
+                }],
+                Methods: vec![String::from("DELETE")],
+                ..Default::default()
+            }),
+        }],
+        ..Default::default()
+    };
+    let encoded = serde_json::to_value(&intention).unwrap();
+    assert!(encoded.get("Action").is_none());
+    assert!(encoded.get("ID").is_none());
+    assert_eq!(encoded["Permissions"][0]["Action"], "deny");
+    assert_eq!(
+        encoded["Permissions"][0]["HTTP"]["Header"][0]["Present"],
+        true
+    );
+    let decoded: Intention = serde_json::from_value(encoded).unwrap();
+    assert_eq!(decoded, intention);
+}
+
+#[tokio::test]
+async fn intention_test() {
+    let client = common::client().await;
+    let intention = Intention {
+        SourceName: String::from("intention-test-web"),
+        DestinationName: String::from("intention-test-api"),
+        Action: Some(IntentionAction::Deny),
+        Description: String::from("no access"),
+        ..Default::default()
+    };
+    client.intention_upsert(&intention, None).await.unwrap();
+
+    let (fetched, _) = client
+        .intention_get_exact("intention-test-web", "intention-test-api", None)
+        .await
+        .unwrap();
+    assert_eq!(fetched.Action, Some(IntentionAction::Deny));
+    assert_eq!(fetched.Description, "no access");
+
+    let (intentions, _) = client.intention_list(None).await.unwrap();
+    assert!(intentions
+        .iter()
+        .any(|i| i.DestinationName == "intention-test-api"));
+
+    let (matches, _) = client
+        .intention_match(IntentionMatchType::Source, "intention-test-web", None)
+        .await
+        .unwrap();
+    assert_eq!(matches[0].DestinationName, "intention-test-api");
+
+    let (allowed, _) = client
+        .intention_check("intention-test-web", "intention-test-api", None)
+        .await
+        .unwrap();
+    assert!(!allowed);
+
+    client
+        .intention_delete_exact("intention-test-web", "intention-test-api", None)
+        .await
+        .unwrap();
+    let (matches, _) = client
+        .intention_match(IntentionMatchType::Destination, "intention-test-api", None)
+        .await
+        .unwrap();
+    assert!(matches.iter().all(|i| i.SourceName != "intention-test-web"));
+}
@@ -1,14 +1,14 @@
 extern crate consul;
 
+mod common;
+
 use base64::Engine;
 use consul::kv::KVPair;
-use consul::{Client, Config};
 
 #[tokio::test]
 async fn kv_test() {
     use consul::kv::KV;
-    let config = Config::new().unwrap();
-    let client = Client::new(config);
+    let client = common::client().await;
     let r = client.list("", None).await.unwrap();
     assert!(r.0.is_empty());
 
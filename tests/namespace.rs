@@ -0,0 +1,85 @@
+mod common;
+
+use consul::namespace::{Namespace, Namespaces};
+use consul::partition::Partitions;
+use consul::{Client, Config, QueryOptions, WriteOptions};
+use tokio::net::TcpListener;
+
+#[tokio::test]
+async fn namespace_scope_test() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = format!("http://{}", listener.local_addr().unwrap());
+    let mut config = Config::new_from_addr(&address, None).unwrap();
+    config.namespace = Some(String::from("team"));
+    config.partition = Some(String::from("tenants"));
+    let client = Client::new(config);
+
+    let list = tokio::spawn({
+        let client = client.clone();
+        async move { client.namespace_list(None).await.map(|r| r.0.len()) }
+    });
+    let request = common::serve_once(&listener, "[]").await;
+    assert_eq!(list.await.unwrap().unwrap(), 0);
+    assert!(request.starts_with("GET /v1/namespaces?"));
+    assert!(request.contains("ns=team"));
+    assert!(request.contains("partition=tenants"));
+
+    let read = tokio::spawn({
+        let client = client.clone();
+        async move {
+            let q = QueryOptions {
+                namespace: Some(String::from("other")),
+                ..Default::default()
+            };
+            client
+                .partition_read("tenants", Some(&q))
+                .await
+                .map(|r| r.0)
+        }
+    });
+    let request = common::serve_once(&listener, r#"{"Name": "tenants"}"#).await;
+    assert_eq!(read.await.unwrap().unwrap().unwrap().Name, "tenants");
+    assert!(request.contains("ns=other"));
+    assert!(request.contains("partition=tenants"));
+
+    let create = tokio::spawn({
+        let client = client.clone();
+        async move {
+            let q = WriteOptions {
+                partition: Some(String::from("default")),
+                ..Default::default()
+            };
+            let namespace = Namespace {
+                Name: String::from("team"),
+                ..Default::default()
+            };
+            client
+                .namespace_create(&namespace, Some(&q))
+                .await
+                .map(|r| r.0)
+        }
+    });
+    let request = common::serve_once(&listener, r#"{"Name": "team", "CreateIndex": 7}"#).await;
+    assert_eq!(create.await.unwrap().unwrap().CreateIndex, Some(7));
+    assert!(request.starts_with("PUT /v1/namespace?"));
+    assert!(request.contains("ns=team"));
+    assert!(request.contains("partition=default"));
+}
+
+#[test]
+fn namespace_env_test() {
+    std::env::set_var("CONSUL_NAMESPACE", "team");
+    std::env::set_var("CONSUL_PARTITION", "tenants");
+    let config = Config::new_from_env().unwrap();
+    assert_eq!(config.namespace.as_deref(), Some("team"));
+    assert_eq!(config.partition.as_deref(), Some("tenants"));
+
+    std::env::set_var("CONSUL_NAMESPACE", "");
+    std::env::set_var("CONSUL_PARTITION", "");
+    let config = Config::new_from_env().unwrap();
+    assert_eq!(config.namespace, None);
+    assert_eq!(config.partition, None);
+
+    std::env::remove_var("CONSUL_NAMESPACE");
+    std::env::remove_var("CONSUL_PARTITION");
+}
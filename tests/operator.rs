@@ -0,0 +1,59 @@
+mod common;
+
+use consul::operator::{KeyringResponse, Operator};
+
+#[tokio::test]
+async fn raft_configuration_test() {
+    let client = common::client().await;
+    let (raft, _) = client.raft_get_configuration(None).await.unwrap();
+    assert_eq!(raft.Servers.len(), 1);
+    assert!(raft.Servers[0].Leader);
+    assert!(raft.Servers[0].Voter);
+}
+
+#[tokio::test]
+async fn autopilot_test() {
+    let client = common::client().await;
+    let (conf, _) = client.autopilot_get_configuration(None).await.unwrap();
+    let (updated, _) = client
+        .autopilot_set_configuration(&conf, true, None)
+        .await
+        .unwrap();
+    assert!(updated);
+    // The index moved, the same update must now be rejected
+    let (updated, _) = client
+        .autopilot_set_configuration(&conf, true, None)
+        .await
+        .unwrap();
+    assert!(!updated);
+
+    let (health, _) = client.autopilot_server_health(None).await.unwrap();
+    assert!(health.Healthy);
+    assert_eq!(health.Servers.len(), 1);
+    assert!(health.server(&health.Servers[0].ID).unwrap().Leader);
+
+    let (state, _) = client.autopilot_state(None).await.unwrap();
+    assert!(state.Servers.contains_key(&state.Leader));
+}
+
+#[test]
+fn keyring_response_test() {
+    let ring = KeyringResponse {
+        Keys: vec![(String::from("new"), 3), (String::from("old"), 2)]
+            .into_iter()
+            .collect(),
+        NumNodes: 3,
+        ..Default::default()
+    };
+    assert!(ring.is_installed_everywhere("new"));
+    assert!(!ring.is_installed_everywhere("old"));
+    assert!(!ring.is_installed_everywhere("unknown"));
+    assert!(ring.is_primary_everywhere("old"));
+
+    let ring = KeyringResponse {
+        PrimaryKeys: vec![(String::from("new"), 3)].into_iter().collect(),
+        ..ring
+    };
+    assert!(ring.is_primary_everywhere("new"));
+    assert!(!ring.is_primary_everywhere("old"));
+}
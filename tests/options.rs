@@ -0,0 +1,117 @@
+mod common;
+
+use consul::catalog::Catalog;
+use consul::operator::Operator;
+use consul::{Client, Config, ConsistencyMode, QueryOptions, WriteOptions};
+use tokio::net::TcpListener;
+
+#[tokio::test]
+async fn token_override_test() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = format!("http://{}", listener.local_addr().unwrap());
+    let config = Config::new_from_addr(&address, Some(String::from("config-token"))).unwrap();
+    let client = Client::new(config);
+
+    let list = tokio::spawn({
+        let client = client.clone();
+        async move { client.datacenters().await.map(|r| r.0) }
+    });
+    let request = common::serve_once(&listener, r#"["dc1"]"#).await;
+    assert_eq!(list.await.unwrap().unwrap(), vec!["dc1"]);
+    assert!(request.contains("x-consul-token: config-token"));
+
+    let list = tokio::spawn({
+        let client = client.with_token("scoped-token");
+        async move { client.datacenters().await.map(|r| r.0) }
+    });
+    let request = common::serve_once(&listener, r#"["dc1"]"#).await;
+    list.await.unwrap().unwrap();
+    assert!(request.contains("x-consul-token: scoped-token"));
+
+    let list = tokio::spawn({
+        let client = client.clone();
+        async move {
+            let q = QueryOptions {
+                token: Some(String::from("query-token")),
+                consistency: Some(ConsistencyMode::Stale),
+                ..Default::default()
+            };
+            client.services(Some(&q)).await.map(|r| r.0)
+        }
+    });
+    let request = common::serve_once(&listener, "{}").await;
+    list.await.unwrap().unwrap();
+    assert!(request.contains("x-consul-token: query-token"));
+    assert!(!request.contains("config-token"));
+    assert!(request.lines().next().unwrap().contains("stale="));
+
+    let install = tokio::spawn({
+        let client = client.clone();
+        async move {
+            let q = WriteOptions {
+                token: Some(String::from("write-token")),
+                relay_factor: Some(2),
+                ..Default::default()
+            };
+            client.keyring_install("key", Some(&q)).await.map(|r| r.0)
+        }
+    });
+    let request = common::serve_once(&listener, "").await;
+    install.await.unwrap().unwrap();
+    assert!(request.starts_with("POST /v1/operator/keyring?"));
+    assert!(request.lines().next().unwrap().contains("relay-factor=2"));
+    assert!(request.contains("x-consul-token: write-token"));
+}
+
+#[tokio::test]
+async fn relay_factor_test() {
+    use consul::errors::Error;
+    use consul::kv::{KVPair, KV};
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = format!("http://{}", listener.local_addr().unwrap());
+    let client = Client::new(Config::new_from_addr(&address, None).unwrap());
+
+    let list = tokio::spawn({
+        let client = client.clone();
+        async move {
+            let q = QueryOptions {
+                relay_factor: Some(3),
+                ..Default::default()
+            };
+            client.keyring_list(Some(&q)).await.map(|r| r.0)
+        }
+    });
+    let request = common::serve_once(&listener, "[]").await;
+    list.await.unwrap().unwrap();
+    assert!(request.starts_with("GET /v1/operator/keyring?"));
+    assert!(request.lines().next().unwrap().contains("relay-factor=3"));
+
+    // Only the keyring operations accept it
+    let put = tokio::spawn({
+        let client = client.clone();
+        async move {
+            let q = WriteOptions {
+                relay_factor: Some(3),
+                ..Default::default()
+            };
+            let pair = KVPair {
+                Key: String::from("relay"),
+                ..Default::default()
+            };
+            client.put(&pair, Some(&q)).await.map(|r| r.0)
+        }
+    });
+    let request = common::serve_once(&listener, "true").await;
+    assert!(put.await.unwrap().unwrap());
+    assert!(!request.contains("relay-factor"));
+
+    let q = WriteOptions {
+        relay_factor: Some(6),
+        ..Default::default()
+    };
+    match client.keyring_use("key", Some(&q)).await {
+        Err(Error::InvalidRelayFactor(6)) => {}
+        other => panic!("Expected InvalidRelayFactor, got {:?}", other),
+    }
+}
@@ -0,0 +1,103 @@
+mod common;
+
+use consul::health::Health;
+use consul::peering::{Peering, PeeringGenerateTokenRequest, PeeringState, PeeringWatch, Peerings};
+use consul::QueryOptions;
+
+#[test]
+fn peering_state_test() {
+    let peering: Peering = serde_json::from_str(
+        r#"{"Name": "cluster-02", "PeeringState": "ACTIVE", "StreamStatus": {"ImportedServices": ["web"]}}"#,
+    )
+    .unwrap();
+    assert_eq!(peering.PeeringState, PeeringState::Active);
+    assert_eq!(peering.StreamStatus.ImportedServices, vec!["web"]);
+
+    let peering: Peering = serde_json::from_str(r#"{"PeeringState": "SOMETHING_NEW"}"#).unwrap();
+    assert_eq!(peering.PeeringState, PeeringState::Undefined);
+}
+
+#[tokio::test]
+async fn peering_test() {
+    let client = common::client().await;
+    let req = PeeringGenerateTokenRequest {
+        PeerName: String::from("peering-test"),
+        ..Default::default()
+    };
+    let (token, _) = client.peering_generate_token(&req, None).await.unwrap();
+    assert!(!token.is_empty());
+
+    let mut watch = PeeringWatch::new(client.clone(), "peering-test");
+    let peering = watch.next().await.unwrap().unwrap();
+    assert_eq!(peering.Name, "peering-test");
+    assert_eq!(peering.PeeringState, PeeringState::Pending);
+
+    let (peerings, _) = client.peering_list(None).await.unwrap();
+    assert!(peerings.iter().any(|p| p.Name == "peering-test"));
+
+    let q = QueryOptions {
+        peer: Some(String::from("peering-test")),
+        ..Default::default()
+    };
+    let (entries, _) = client.service("web", None, false, Some(&q)).await.unwrap();
+    assert!(entries.is_empty());
+
+    client.peering_delete("peering-test", None).await.unwrap();
+    // The peering is either being deleted or already gone
+    if let Some(peering) = watch.next().await.unwrap() {
+        assert_eq!(peering.PeeringState, PeeringState::Deleting);
+    }
+
+    let (missing, _) = client.peering_read("peering-unknown", None).await.unwrap();
+    assert!(missing.is_none());
+}
+
+#[tokio::test]
+async fn peering_watch_poll_test() {
+    use consul::errors::Error;
+    use consul::{Client, Config};
+    use std::sync::atomic::Ordering;
+    use std::time::Duration;
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = format!("http://{}", listener.local_addr().unwrap());
+    let served = common::serve_all(
+        listener,
+        r#"{"Name": "cluster-02", "PeeringState": "PENDING"}"#,
+        Some(1),
+    );
+    let client = Client::new(Config::new_from_addr(&address, None).unwrap());
+
+    let mut watch = PeeringWatch::new(client, "cluster-02");
+    let waited = watch
+        .wait_for(&[PeeringState::Active], Duration::from_millis(2500))
+        .await;
+    assert!(matches!(waited, Err(Error::PeeringTimeout(_))));
+    // The first state is returned right away, then an unchanged state is read again
+    // right away and after 1s, the next read would be 2s later
+    assert!(served.load(Ordering::SeqCst) <= 3);
+}
+
+#[tokio::test]
+async fn peering_watch_current_state_test() {
+    use consul::{Client, Config};
+    use std::time::Duration;
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = format!("http://{}", listener.local_addr().unwrap());
+    common::serve_all(
+        listener,
+        r#"{"Name": "cluster-02", "PeeringState": "PENDING"}"#,
+        Some(1),
+    );
+    let client = Client::new(Config::new_from_addr(&address, None).unwrap());
+
+    let mut watch = PeeringWatch::new(client, "cluster-02");
+    let peering = watch.next().await.unwrap().unwrap();
+    assert_eq!(peering.PeeringState, PeeringState::Pending);
+    let peering = watch
+        .wait_for(&[PeeringState::Pending], Duration::from_millis(500))
+        .await
+        .unwrap();
+    assert_eq!(peering.Name, "cluster-02");
+}
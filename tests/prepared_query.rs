@@ -0,0 +1,69 @@
+mod common;
+
+use consul::prepared_query::{
+    PreparedQuery, PreparedQueryDefinition, QueryFailoverOptions, QueryTemplate, ServiceQuery,
+};
+
+#[tokio::test]
+async fn prepared_query_test() {
+    let client = common::client().await;
+    let mut query = PreparedQueryDefinition {
+        Name: String::from("prepared-query-test"),
+        Service: ServiceQuery {
+            Service: String::from("consul"),
+            OnlyPassing: true,
+            Failover: QueryFailoverOptions {
+                NearestN: 2,
+                Datacenters: vec![String::from("dc2")],
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        ..Default::default()
+    };
+    let (id, _) = client.query_create(&query, None).await.unwrap();
+    assert!(!id.is_empty());
+
+    let (fetched, _) = client.query_get(&id, None).await.unwrap();
+    let fetched = fetched.unwrap();
+    assert_eq!(fetched.Service.Failover.NearestN, 2);
+
+    query.ID = id.clone();
+    query.DNS.TTL = String::from("10s");
+    client.query_update(&query, None).await.unwrap();
+    let (queries, _) = client.query_list(None).await.unwrap();
+    assert!(queries.iter().any(|q| q.ID == id && q.DNS.TTL == "10s"));
+
+    let (result, _) = client
+        .query_execute("prepared-query-test", None, Some(1), None)
+        .await
+        .unwrap();
+    assert_eq!(result.Service, "consul");
+    assert_eq!(result.Nodes.len(), 1);
+    assert_eq!(result.Failovers, 0);
+
+    client.query_delete(&id, None).await.unwrap();
+    let (deleted, _) = client.query_get(&id, None).await.unwrap();
+    assert!(deleted.is_none());
+}
+
+#[tokio::test]
+async fn prepared_query_template_test() {
+    let client = common::client().await;
+    let template = PreparedQueryDefinition {
+        Name: String::from("prepared-query-template-"),
+        Service: ServiceQuery {
+            Service: String::from("${name.suffix}"),
+            ..Default::default()
+        },
+        Template: Some(QueryTemplate::name_prefix_match(None)),
+        ..Default::default()
+    };
+    let (id, _) = client.query_create(&template, None).await.unwrap();
+    let (explained, _) = client
+        .query_explain("prepared-query-template-consul", None)
+        .await
+        .unwrap();
+    assert_eq!(explained.Query.Service.Service, "consul");
+    client.query_delete(&id, None).await.unwrap();
+}
@@ -0,0 +1,72 @@
+mod common;
+
+use std::time::Duration;
+
+use consul::agent::{Agent, RegisterAgentService, TTLStatus};
+use consul::errors::Error;
+use consul::registration::ServiceRegistration;
+use consul::{Client, Config};
+
+#[tokio::test]
+async fn registration_heartbeat_test() {
+    let client = common::client().await;
+    let registration = ServiceRegistration::register(
+        client.clone(),
+        RegisterAgentService::new("registration-test").port(11460),
+        Duration::from_secs(3),
+        || (TTLStatus::WARN, Some(String::from("degraded"))),
+    )
+    .await
+    .unwrap();
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    let (health, _) = client
+        .health_service_by_id("registration-test", None)
+        .await
+        .unwrap();
+    assert_eq!(health.AggregatedStatus, "warning");
+
+    registration.shutdown().await.unwrap();
+    let services = client
+        .agent_services(Some(r#"ID == "registration-test""#))
+        .await
+        .unwrap();
+    assert!(services.is_empty());
+}
+
+#[tokio::test]
+async fn registration_reregister_test() {
+    let client = common::client().await;
+    let registration = ServiceRegistration::register(
+        client.clone(),
+        RegisterAgentService::new("registration-reregister-test").port(11461),
+        Duration::from_secs(3),
+        || (TTLStatus::PASS, None),
+    )
+    .await
+    .unwrap();
+    // Simulates an agent that lost its state
+    client
+        .deregister_service(registration.service_id())
+        .await
+        .unwrap();
+    tokio::time::sleep(Duration::from_millis(1500)).await;
+    let (health, _) = client
+        .health_service_by_id(registration.service_id(), None)
+        .await
+        .unwrap();
+    assert_eq!(health.AggregatedStatus, "passing");
+    registration.shutdown().await.unwrap();
+}
+
+#[tokio::test]
+async fn registration_invalid_ttl_test() {
+    let client = Client::new(Config::new().unwrap());
+    let registration = ServiceRegistration::register(
+        client,
+        RegisterAgentService::new("registration-invalid-ttl-test"),
+        Duration::from_nanos(2),
+        || (TTLStatus::PASS, None),
+    )
+    .await;
+    assert!(matches!(registration, Err(Error::InvalidTtl(_))));
+}
@@ -0,0 +1,56 @@
+mod common;
+
+use consul::resolver::{ConsulResolver, ServiceName};
+
+#[test]
+fn service_name_parse_test() {
+    assert_eq!(
+        ServiceName::parse("web.service.consul", "consul"),
+        Some(ServiceName {
+            service: String::from("web"),
+            tag: None,
+            datacenter: None,
+        })
+    );
+    assert_eq!(
+        ServiceName::parse("v1.web.service.dc2.consul.", "consul"),
+        Some(ServiceName {
+            service: String::from("web"),
+            tag: Some(String::from("v1")),
+            datacenter: Some(String::from("dc2")),
+        })
+    );
+    assert_eq!(
+        ServiceName::parse("web.service.example.com", "example.com"),
+        Some(ServiceName {
+            service: String::from("web"),
+            tag: None,
+            datacenter: None,
+        })
+    );
+    assert_eq!(ServiceName::parse("web.node.consul", "consul"), None);
+    assert_eq!(ServiceName::parse("www.example.com", "consul"), None);
+    assert_eq!(ServiceName::parse("service.consul", "consul"), None);
+}
+
+#[tokio::test]
+async fn resolver_reqwest_test() {
+    let resolver = ConsulResolver::new(common::client().await);
+    let addrs = resolver
+        .lookup(&ServiceName::parse("consul.service.consul", "consul").unwrap())
+        .await
+        .unwrap();
+    assert!(!addrs.is_empty());
+
+    // The agent HTTP API, reached through the `consul` service address
+    let http = reqwest::Client::builder()
+        .dns_resolver(std::sync::Arc::new(resolver))
+        .build()
+        .unwrap();
+    let response = http
+        .get("http://consul.service.consul:8500/v1/status/leader")
+        .send()
+        .await
+        .unwrap();
+    assert!(response.status().is_success());
+}
@@ -1,14 +1,16 @@
 extern crate consul;
 extern crate rand;
 
+mod common;
+
 use consul::session::{Session, SessionEntry};
-use consul::{Client, Config};
+use consul::Client;
 use rand::distributions::Alphanumeric;
 use rand::{thread_rng, Rng};
 
 #[tokio::test]
 async fn session_create_test() {
-    let (client, unique_test_identifier) = set_up();
+    let (client, unique_test_identifier) = set_up().await;
 
     assert_eq!(
         get_number_of_session_entries_with_matching_name(&client, &unique_test_identifier).await,
@@ -32,7 +34,7 @@ async fn session_create_test() {
 
 #[tokio::test]
 async fn session_destroy_test() {
-    let (client, unique_test_identifier) = set_up();
+    let (client, unique_test_identifier) = set_up().await;
 
     let entry = SessionEntry {
         Name: Some(unique_test_identifier.to_string()),
@@ -63,7 +65,7 @@ async fn session_destroy_test() {
 
 #[tokio::test]
 async fn session_info_test() {
-    let (client, unique_test_identifier) = set_up();
+    let (client, unique_test_identifier) = set_up().await;
 
     let entry = SessionEntry {
         Name: Some(unique_test_identifier.to_string()),
@@ -78,6 +80,7 @@ async fn session_info_test() {
 
     assert_eq!(session_entries.len(), 1);
 
+    #[allow(clippy::get_first)]
     let session_entry = session_entries.get(0);
 
     assert_eq!(
@@ -90,7 +93,7 @@ async fn session_info_test() {
 
 #[tokio::test]
 async fn session_list_test() {
-    let (client, unique_test_identifier) = set_up();
+    let (client, unique_test_identifier) = set_up().await;
 
     let entry_names = vec![
         format!("{}-1", unique_test_identifier),
@@ -136,7 +139,7 @@ async fn session_list_test() {
 
 #[tokio::test]
 async fn session_node_test() {
-    let (client, unique_test_identifier) = set_up();
+    let (client, unique_test_identifier) = set_up().await;
 
     let entry = SessionEntry {
         Name: Some(unique_test_identifier.to_string()),
@@ -163,7 +166,7 @@ async fn session_node_test() {
 
 #[tokio::test]
 async fn session_renew_test() {
-    let (client, unique_test_identifier) = set_up();
+    let (client, unique_test_identifier) = set_up().await;
 
     let entry = SessionEntry {
         Name: Some(unique_test_identifier),
@@ -179,9 +182,8 @@ async fn session_renew_test() {
     tear_down(&client, &created_session_entry_id).await;
 }
 
-fn set_up() -> (Client, String) {
-    let config = Config::new().unwrap();
-    let client = Client::new(config);
+async fn set_up() -> (Client, String) {
+    let client = common::client().await;
 
     let unique_test_identifier: String = thread_rng()
         .sample_iter(&Alphanumeric)
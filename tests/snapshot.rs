@@ -0,0 +1,55 @@
+mod common;
+
+use std::io::Cursor;
+
+use consul::snapshot::{inspect, Snapshot, SnapshotMeta};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+
+fn archive(files: &[(&str, &[u8])]) -> Vec<u8> {
+    let mut builder = tar::Builder::new(GzEncoder::new(Vec::new(), Compression::default()));
+    for (name, content) in files {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(content.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder.append_data(&mut header, name, *content).unwrap();
+    }
+    builder.into_inner().unwrap().finish().unwrap()
+}
+
+#[test]
+fn snapshot_inspect_test() {
+    let meta = br#"{"Version":1,"ID":"2-13-1602222343949","Index":13,"Term":2,"Peers":"ka","ConfigurationIndex":1,"Size":2042}"#;
+    let data = archive(&[("meta.json", meta), ("state.bin", b"state")]);
+    assert_eq!(
+        inspect(Cursor::new(data)).unwrap(),
+        SnapshotMeta {
+            Version: 1,
+            ID: String::from("2-13-1602222343949"),
+            Index: 13,
+            Term: 2,
+            ConfigurationIndex: 1,
+            Size: 2042,
+        }
+    );
+
+    let data = archive(&[("state.bin", b"state")]);
+    assert!(inspect(Cursor::new(data)).is_err());
+}
+
+#[tokio::test]
+async fn snapshot_save_restore_test() {
+    let client = common::client().await;
+    let mut data = Vec::new();
+    let (written, meta) = client.snapshot_save(&mut data, false, None).await.unwrap();
+    assert_eq!(written as usize, data.len());
+
+    let snapshot = inspect(Cursor::new(&data)).unwrap();
+    assert_eq!(Some(snapshot.Index), meta.last_index);
+
+    client
+        .snapshot_restore(Cursor::new(data), None)
+        .await
+        .unwrap();
+}
@@ -0,0 +1,72 @@
+use consul::connect_ca::CARootList;
+use consul::spiffe::SpiffeId;
+
+const TRUST_DOMAIN: &str = "11111111-2222-3333-4444-555555555555.consul";
+
+#[test]
+fn spiffe_id_test() {
+    let uri = format!("spiffe://{}/ns/default/dc/dc1/svc/web", TRUST_DOMAIN);
+    let id: SpiffeId = uri.parse().unwrap();
+    assert_eq!(id, SpiffeId::service(TRUST_DOMAIN, "default", "dc1", "web"));
+    assert_eq!(id.service_name(), Some("web"));
+    assert_eq!(id.partition(), None);
+    assert_eq!(id.to_string(), uri);
+
+    let uri = format!(
+        "spiffe://{}/ap/team/ns/billing/dc/dc2/svc/api",
+        TRUST_DOMAIN
+    );
+    let id: SpiffeId = uri.parse().unwrap();
+    assert_eq!(id.partition(), Some("team"));
+    assert_eq!(id.datacenter(), "dc2");
+    assert_eq!(id.to_string(), uri);
+
+    let uri = format!("spiffe://{}/agent/client/dc/dc1/id/node-1", TRUST_DOMAIN);
+    let id: SpiffeId = uri.parse().unwrap();
+    assert!(matches!(id, SpiffeId::Agent { ref node_id, .. } if node_id == "node-1"));
+    assert_eq!(id.service_name(), None);
+    assert_eq!(id.to_string(), uri);
+
+    let uri = format!("spiffe://{}/ap/team/gateway/mesh/dc/dc1", TRUST_DOMAIN);
+    let id: SpiffeId = uri.parse().unwrap();
+    assert!(matches!(id, SpiffeId::MeshGateway { .. }));
+    assert_eq!(id.to_string(), uri);
+
+    for invalid in &[
+        "https://example.consul/ns/default/dc/dc1/svc/web",
+        "spiffe:///ns/default/dc/dc1/svc/web",
+        "spiffe://example.consul/ns/default/dc/dc1/svc/",
+        "spiffe://example.consul/ns/default/dc/dc1/svc/web/extra",
+        "spiffe://example.consul/agent/server/dc/dc1",
+        "spiffe://example.consul",
+    ] {
+        assert!(invalid.parse::<SpiffeId>().is_err(), "{}", invalid);
+    }
+}
+
+#[test]
+fn spiffe_trust_domain_test() {
+    let roots: CARootList =
+        serde_json::from_value(serde_json::json!({ "TrustDomain": TRUST_DOMAIN })).unwrap();
+    let id = SpiffeId::service(&TRUST_DOMAIN.to_uppercase(), "default", "dc1", "web");
+    assert!(id.is_trusted(TRUST_DOMAIN));
+    assert!(id.validate(&roots).is_ok());
+
+    let id = SpiffeId::service("other.consul", "default", "dc1", "web");
+    assert!(!id.is_trusted(TRUST_DOMAIN));
+    assert!(id.validate(&roots).is_err());
+}
+
+#[test]
+fn spiffe_id_matches_test() {
+    let id: SpiffeId = "spiffe://ABC.consul/ap/default/ns/default/dc/dc1/svc/web"
+        .parse()
+        .unwrap();
+    assert!(id.matches(&SpiffeId::service("abc.consul", "default", "dc1", "web")));
+    assert!(!id.matches(&SpiffeId::service("abc.consul", "other", "dc1", "web")));
+    assert!(!id.matches(&SpiffeId::service("abc.consul", "default", "dc2", "web")));
+    let other_partition: SpiffeId = "spiffe://abc.consul/ap/team/ns/default/dc/dc1/svc/web"
+        .parse()
+        .unwrap();
+    assert!(!id.matches(&other_partition));
+}
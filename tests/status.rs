@@ -0,0 +1,44 @@
+use std::time::Duration;
+
+use consul::errors::Error;
+use consul::status::Status;
+use consul::{Client, Config};
+
+#[tokio::test]
+async fn status_test() {
+    let client = Client::new(Config::new().unwrap());
+    client
+        .wait_until_ready(Duration::from_secs(30))
+        .await
+        .unwrap();
+    let (leader, _) = client.leader(None).await.unwrap();
+    assert!(!leader.is_empty());
+    let (peers, _) = client.peers(None).await.unwrap();
+    assert_eq!(peers, [leader]);
+}
+
+#[tokio::test]
+async fn wait_until_ready_timeout_test() {
+    // Nothing listens on the discard port
+    let config = Config::new_from_addr("http://127.0.0.1:9", None).unwrap();
+    let client = Client::new(config);
+    match client.wait_until_ready(Duration::from_millis(300)).await {
+        Err(Error::NotReady(_)) => {}
+        other => panic!("Expected NotReady, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn wait_until_ready_hung_agent_test() {
+    // Accepts connections but never answers
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = format!("http://{}", listener.local_addr().unwrap());
+    let client = Client::new(Config::new_from_addr(&address, None).unwrap());
+    let start = std::time::Instant::now();
+    match client.wait_until_ready(Duration::from_millis(300)).await {
+        Err(Error::NotReady(_)) => {}
+        other => panic!("Expected NotReady, got {:?}", other),
+    }
+    assert!(start.elapsed() < Duration::from_secs(2));
+    drop(listener);
+}